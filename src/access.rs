@@ -0,0 +1,122 @@
+//! Access permissions of a [`VolatileRef`](../struct.VolatileRef.html) and
+//! of the fields of a register block
+
+use core::mem;
+
+use {RO, RW, WO};
+
+/// Reads and writes are allowed
+pub struct ReadWrite;
+
+/// Only reads are allowed
+pub struct ReadOnly;
+
+/// Only writes are allowed
+pub struct WriteOnly;
+
+/// Access permissions that allow reads
+pub trait Readable: private::Sealed {}
+
+/// Access permissions that allow writes
+pub trait Writable: private::Sealed {}
+
+impl Readable for ReadWrite {}
+impl Writable for ReadWrite {}
+impl Readable for ReadOnly {}
+impl Writable for WriteOnly {}
+
+mod private {
+    pub trait Sealed {}
+
+    impl Sealed for super::ReadWrite {}
+    impl Sealed for super::ReadOnly {}
+    impl Sealed for super::WriteOnly {}
+}
+
+/// Kind of access a register allows
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// Reads and writes are allowed
+    ReadWrite,
+    /// Only reads are allowed
+    ReadOnly,
+    /// Only writes are allowed
+    WriteOnly,
+}
+
+impl Kind {
+    /// Returns `true` if this kind of register can be read
+    pub fn is_readable(self) -> bool {
+        self != Kind::WriteOnly
+    }
+
+    /// Returns `true` if this kind of register can be written
+    pub fn is_writable(self) -> bool {
+        self != Kind::ReadOnly
+    }
+}
+
+/// Types that can be fields of a [`register_block!`](../macro.register_block.html)
+///
+/// Plain integers, and arrays of them, are reserved space: they don't
+/// describe a register.
+pub trait HasAccess {
+    /// Allowed accesses, or `None` for reserved space
+    const ACCESS: Option<Kind>;
+    /// Size of a single access, in bytes; smaller than the type for arrays
+    const WIDTH: usize;
+    /// `true` if reads have no side effects, see [`Pure`](../struct.Pure.html)
+    const PURE: bool = false;
+    /// `true` if the register is accessed as two half-width accesses, see
+    /// [`SplitAccess`](../struct.SplitAccess.html)
+    const SPLIT: bool = false;
+}
+
+impl<T> HasAccess for RO<T>
+    where T: Copy
+{
+    const ACCESS: Option<Kind> = Some(Kind::ReadOnly);
+    const WIDTH: usize = mem::size_of::<T>();
+}
+
+impl<T> HasAccess for RW<T>
+    where T: Copy
+{
+    const ACCESS: Option<Kind> = Some(Kind::ReadWrite);
+    const WIDTH: usize = mem::size_of::<T>();
+}
+
+impl<T> HasAccess for WO<T>
+    where T: Copy
+{
+    const ACCESS: Option<Kind> = Some(Kind::WriteOnly);
+    const WIDTH: usize = mem::size_of::<T>();
+}
+
+impl<R, const N: usize> HasAccess for [R; N]
+    where R: HasAccess
+{
+    const ACCESS: Option<Kind> = R::ACCESS;
+    const WIDTH: usize = R::WIDTH;
+    const PURE: bool = R::PURE;
+    const SPLIT: bool = R::SPLIT;
+}
+
+macro_rules! reserved {
+    ($($ty:ty,)+) => {
+        $(
+            impl HasAccess for $ty {
+                const ACCESS: Option<Kind> = None;
+                const WIDTH: usize = mem::size_of::<$ty>();
+            }
+        )+
+    }
+}
+
+reserved! {
+    u8,
+    u16,
+    u32,
+    u64,
+    usize,
+}
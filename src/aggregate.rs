@@ -0,0 +1,157 @@
+//! Element-wise access to array payloads
+//!
+//! `read` and `write` access the whole payload with a single volatile
+//! operation. For payloads wider than the bus, like the multi-byte records of
+//! message-box registers, the compiler is free to split that operation into
+//! any sequence of narrower accesses, which hardware may not accept.
+//!
+//! For such registers use an array of the width the hardware expects as
+//! payload, e.g. `RW<[u8; 4]>` or `RW<[u32; 2]>`, and the element-wise
+//! methods below: they perform exactly one volatile access per element, in
+//! ascending index order. Records with fields of different widths are better
+//! described as a `#[repr(C)]` struct of registers.
+//!
+//! ```
+//! use volatile_register::RW;
+//!
+//! let mut memory = [0u8; 4];
+//! let mailbox = unsafe { &*(memory.as_mut_ptr() as *const RW<[u8; 4]>) };
+//!
+//! unsafe { mailbox.write_elements([1, 2, 3, 4]) }
+//! assert_eq!(mailbox.read_element(2), 3);
+//! ```
+
+use core::mem;
+
+use {raw, RO, RW, WO};
+
+#[inline(always)]
+fn element<T, const N: usize>(array: *mut [T; N], index: usize) -> *mut T {
+    assert!(index < N, "element index out of bounds");
+    (array as *mut T).wrapping_add(index)
+}
+
+impl<T, const N: usize> RO<[T; N]>
+    where T: Copy
+{
+    /// Reads element `index` of the payload
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub fn read_element(&self, index: usize) -> T {
+        unsafe { raw::read(element(self.register.as_ptr(), index)) }
+    }
+
+    /// Reads the payload one element at a time, in ascending index order
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub fn read_elements(&self) -> [T; N] {
+        read_elements(self.register.as_ptr())
+    }
+}
+
+impl<T, const N: usize> RW<[T; N]>
+    where T: Copy
+{
+    /// Reads element `index` of the payload
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub fn read_element(&self, index: usize) -> T {
+        unsafe { raw::read(element(self.register.as_ptr(), index)) }
+    }
+
+    /// Reads the payload one element at a time, in ascending index order
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub fn read_elements(&self) -> [T; N] {
+        read_elements(self.register.as_ptr())
+    }
+
+    /// Writes `value` into element `index` of the payload
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn write_element(&self, index: usize, value: T) {
+        raw::write(element(self.register.as_ptr(), index), value)
+    }
+
+    /// Writes `values` one element at a time, in ascending index order
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn write_elements(&self, values: [T; N]) {
+        write_elements(self.register.as_ptr(), values)
+    }
+}
+
+impl<T, const N: usize> WO<[T; N]>
+    where T: Copy
+{
+    /// Writes `value` into element `index` of the payload
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn write_element(&self, index: usize, value: T) {
+        raw::write(element(self.as_ptr(), index), value)
+    }
+
+    /// Writes `values` one element at a time, in ascending index order
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn write_elements(&self, values: [T; N]) {
+        write_elements(self.as_ptr(), values)
+    }
+}
+
+#[inline(always)]
+#[cfg_attr(feature = "timing", track_caller)]
+fn read_elements<T, const N: usize>(array: *mut [T; N]) -> [T; N]
+    where T: Copy
+{
+    let mut values = mem::MaybeUninit::<[T; N]>::uninit();
+    let first = values.as_mut_ptr() as *mut T;
+    for index in 0..N {
+        unsafe {
+            first.add(index).write(raw::read(element(array, index)));
+        }
+    }
+    unsafe { values.assume_init() }
+}
+
+#[inline(always)]
+#[cfg_attr(feature = "timing", track_caller)]
+unsafe fn write_elements<T, const N: usize>(array: *mut [T; N], values: [T; N])
+    where T: Copy
+{
+    for (index, value) in values.iter().enumerate() {
+        raw::write(element(array, index), *value);
+    }
+}
@@ -0,0 +1,273 @@
+//! Register access backends
+//!
+//! Registers are not always memory mapped: they can sit behind an external
+//! bus (I2C, SPI), a VFIO region or a debug probe, where any access can fail.
+//! A [`Backend`] performs the accesses for a register address space, and
+//! [`Register`] is a handle to one register of that space. Both surface
+//! failures through [`TryRead`] and [`TryWrite`], which the memory mapped
+//! register types (`RO`, `RW` and `WO`) implement as well, with
+//! `Infallible` as error type, so that drivers can be written once for both.
+//!
+//! [`TryRead`]: ../trait.TryRead.html
+//! [`TryWrite`]: ../trait.TryWrite.html
+//!
+//! ```
+//! use volatile_register::access::ReadOnly;
+//! use volatile_register::backend::{Backend, Register};
+//! use volatile_register::TryRead;
+//!
+//! /// Registers of an I2C device
+//! pub struct Sensor;
+//!
+//! #[derive(Debug)]
+//! pub struct Nack;
+//!
+//! impl Backend<u8> for Sensor {
+//!     type Error = Nack;
+//!
+//!     unsafe fn try_read(&self, address: usize) -> Result<u8, Nack> {
+//!         // .. I2C transfer ..
+//!         # let _ = address;
+//!         Ok(0x5a)
+//!     }
+//!
+//!     unsafe fn try_write(&self, address: usize, value: u8) -> Result<(), Nack> {
+//!         // .. I2C transfer ..
+//!         # let _ = (address, value);
+//!         Ok(())
+//!     }
+//! }
+//!
+//! fn chip_id<R: TryRead<u8>>(register: &R) -> Result<u8, R::Error> {
+//!     register.try_read()
+//! }
+//!
+//! let sensor = Sensor;
+//! let who_am_i = unsafe { Register::<_, u8, ReadOnly>::new(&sensor, 0x0f) };
+//! assert_eq!(chip_id(&who_am_i).unwrap(), 0x5a);
+//! ```
+
+use core::convert::Infallible;
+use core::marker::PhantomData;
+
+use access::{ReadWrite, Readable, Writable};
+use poll::Backoff;
+use {raw, TryRead, TryWrite};
+
+/// Performs the accesses to a register address space
+pub trait Backend<T>
+    where T: Copy
+{
+    /// Error reported by failed accesses
+    type Error;
+
+    /// Reads the register at `address`
+    ///
+    /// # Safety
+    ///
+    /// `address` must be a register of this address space that can be read
+    /// as a `T`
+    unsafe fn try_read(&self, address: usize) -> Result<T, Self::Error>;
+
+    /// Writes `value` into the register at `address`
+    ///
+    /// # Safety
+    ///
+    /// `address` must be a register of this address space that can be
+    /// written as a `T`; writes to a register are side effectful
+    unsafe fn try_write(&self, address: usize, value: T) -> Result<(), Self::Error>;
+}
+
+/// Memory mapped registers, accessed with volatile loads and stores
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Mmio;
+
+impl<T> Backend<T> for Mmio
+    where T: Copy
+{
+    type Error = Infallible;
+
+    #[inline(always)]
+    unsafe fn try_read(&self, address: usize) -> Result<T, Infallible> {
+        Ok(raw::read(address as *const T))
+    }
+
+    #[inline(always)]
+    unsafe fn try_write(&self, address: usize, value: T) -> Result<(), Infallible> {
+        raw::write(address as *mut T, value);
+        Ok(())
+    }
+}
+
+/// Register at `address` in the address space of backend `B`
+///
+/// The access permissions, `A`, are part of the type, like for
+/// [`VolatileRef`](../struct.VolatileRef.html).
+pub struct Register<'b, B, T, A = ReadWrite>
+    where B: 'b
+{
+    backend: &'b B,
+    address: usize,
+    _marker: PhantomData<(T, A)>,
+}
+
+impl<'b, B, T, A> Register<'b, B, T, A>
+    where B: Backend<T>, T: Copy
+{
+    /// Creates a handle to the register at `address`
+    ///
+    /// # Safety
+    ///
+    /// `address` must be a register of `backend` that allows the accesses
+    /// permitted by `A` as a `T`
+    pub const unsafe fn new(backend: &'b B, address: usize) -> Self {
+        Register { backend, address, _marker: PhantomData }
+    }
+
+    /// Returns the address of the register
+    pub fn address(&self) -> usize {
+        self.address
+    }
+}
+
+impl<'b, B, T, A> TryRead<T> for Register<'b, B, T, A>
+    where B: Backend<T>, T: Copy, A: Readable
+{
+    type Error = B::Error;
+
+    #[inline(always)]
+    fn try_read(&self) -> Result<T, B::Error> {
+        unsafe { self.backend.try_read(self.address) }
+    }
+}
+
+impl<'b, B, T, A> TryWrite<T> for Register<'b, B, T, A>
+    where B: Backend<T>, T: Copy, A: Writable
+{
+    type Error = B::Error;
+
+    #[inline(always)]
+    unsafe fn try_write(&self, value: T) -> Result<(), B::Error> {
+        self.backend.try_write(self.address, value)
+    }
+}
+
+/// Retries the failed accesses to register `R`
+///
+/// Accesses over a noisy external bus fail now and then: an I2C device
+/// NACKs while it's busy, a probe times out. `Retrying` performs each access
+/// at most `max_attempts` times, calling a fresh copy of `backoff` between
+/// two attempts, and reports the error of the last attempt once they're all
+/// exhausted.
+///
+/// A retried write can reach the device more than once, if the error was
+/// reported after the device accepted it; only wrap registers whose writes
+/// are idempotent.
+///
+/// ```
+/// use std::cell::Cell;
+///
+/// use volatile_register::backend::{Backend, Exhausted, Register, Retrying};
+/// use volatile_register::poll::Spin;
+/// use volatile_register::TryRead;
+///
+/// /// I2C device that NACKs the first transfers
+/// pub struct Sensor {
+///     busy: Cell<usize>,
+/// }
+///
+/// #[derive(Debug, PartialEq)]
+/// pub struct Nack;
+///
+/// impl Backend<u8> for Sensor {
+///     type Error = Nack;
+///
+///     unsafe fn try_read(&self, _: usize) -> Result<u8, Nack> {
+///         if self.busy.get() == 0 {
+///             return Ok(0x5a);
+///         }
+///         self.busy.set(self.busy.get() - 1);
+///         Err(Nack)
+///     }
+///
+///     unsafe fn try_write(&self, _: usize, _: u8) -> Result<(), Nack> {
+///         Ok(())
+///     }
+/// }
+///
+/// let sensor = Sensor { busy: Cell::new(2) };
+/// let who_am_i = unsafe { Register::<_, u8>::new(&sensor, 0x0f) };
+///
+/// let retrying = Retrying::new(&who_am_i, 3, Spin);
+/// assert_eq!(retrying.try_read(), Ok(0x5a));
+///
+/// sensor.busy.set(3);
+/// assert_eq!(retrying.try_read(), Err(Exhausted { attempts: 3, last: Nack }));
+/// ```
+pub struct Retrying<'r, R, B>
+    where R: 'r
+{
+    register: &'r R,
+    max_attempts: usize,
+    backoff: B,
+}
+
+impl<'r, R, B> Retrying<'r, R, B>
+    where B: Backoff + Clone
+{
+    /// Wraps `register`, performing each access at most `max_attempts`
+    /// times, and at least once
+    pub fn new(register: &'r R, max_attempts: usize, backoff: B) -> Self {
+        Retrying { register, max_attempts, backoff }
+    }
+
+    fn retry<F, U, E>(&self, mut access: F) -> Result<U, Exhausted<E>>
+        where F: FnMut() -> Result<U, E>
+    {
+        let mut backoff = self.backoff.clone();
+        let mut attempts = 1;
+        loop {
+            match access() {
+                Ok(value) => return Ok(value),
+                Err(last) if attempts >= self.max_attempts => {
+                    return Err(Exhausted { attempts, last })
+                }
+                Err(_) => {
+                    backoff.backoff();
+                    attempts += 1;
+                }
+            }
+        }
+    }
+}
+
+impl<'r, R, B, T> TryRead<T> for Retrying<'r, R, B>
+    where R: TryRead<T>, B: Backoff + Clone, T: Copy
+{
+    type Error = Exhausted<R::Error>;
+
+    #[inline]
+    fn try_read(&self) -> Result<T, Exhausted<R::Error>> {
+        self.retry(|| self.register.try_read())
+    }
+}
+
+impl<'r, R, B, T> TryWrite<T> for Retrying<'r, R, B>
+    where R: TryWrite<T>, B: Backoff + Clone, T: Copy
+{
+    type Error = Exhausted<R::Error>;
+
+    #[inline]
+    unsafe fn try_write(&self, value: T) -> Result<(), Exhausted<R::Error>> {
+        self.retry(|| self.register.try_write(value))
+    }
+}
+
+/// Error returned by [`Retrying`] when every attempt failed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Exhausted<E> {
+    /// Number of attempts made
+    pub attempts: usize,
+    /// Error reported by the last attempt
+    pub last: E,
+}
@@ -0,0 +1,126 @@
+use interrupt::CriticalSection;
+use {Read, Write};
+
+/// Register that sits behind a bank (window) select register
+///
+/// Some peripherals, like Ethernet PHYs or timers, multiplex several register
+/// banks at the same addresses and expose the selected bank through a select
+/// register. A `Banked` ties the target register to the select register and
+/// to the bank it belongs to, and selects that bank before every access so
+/// the register can't be accessed while another bank is selected.
+///
+/// Accesses take a [`CriticalSection`](interrupt/struct.CriticalSection.html),
+/// so that an interrupt handler can't select another bank between the
+/// selection and the access.
+///
+/// ```
+/// use volatile_register::{Banked, Read, Write, RW};
+/// # #[cfg(feature = "std")]
+/// use volatile_register::interrupt;
+///
+/// // A PHY emulated in RAM: `data` accesses the register of the selected
+/// // page
+/// pub struct Data<'a> {
+///     page: &'a RW<u16>,
+///     pages: &'a [RW<u16>; 4],
+/// }
+///
+/// impl<'a> Read<u16> for Data<'a> {
+///     fn read(&self) -> u16 {
+///         self.pages[usize::from(self.page.read())].read()
+///     }
+/// }
+///
+/// impl<'a> Write<u16> for Data<'a> {
+///     unsafe fn write(&self, value: u16) {
+///         self.pages[usize::from(self.page.read())].write(value)
+///     }
+/// }
+///
+/// # #[cfg(feature = "std")]
+/// # fn main() {
+/// let mut memory = [0u16; 5];
+/// let page = unsafe { &*(memory.as_mut_ptr() as *const RW<u16>) };
+/// let pages = unsafe { &*(memory.as_mut_ptr().add(1) as *const [RW<u16>; 4]) };
+/// let data = Data { page, pages };
+/// assert_eq!(page.read(), 0);
+///
+/// // The LED control register is `data` in page 3
+/// let led_control = Banked::new(page, 3, &data);
+/// interrupt::free(|cs| unsafe { led_control.modify(cs, |r| r | 0x1) });
+///
+/// // the page was selected first, so the write landed in page 3
+/// assert_eq!(memory, [3, 0, 0, 0, 1]);
+/// # }
+/// # #[cfg(not(feature = "std"))]
+/// # fn main() {}
+/// ```
+pub struct Banked<'a, S, B, R>
+    where S: 'a, B: Copy, R: 'a
+{
+    select: &'a S,
+    bank: B,
+    register: &'a R,
+}
+
+impl<'a, S, B, R> Banked<'a, S, B, R>
+    where S: Write<B>, B: Copy
+{
+    /// Ties `register` to `bank`, selected by writing `bank` into `select`
+    pub fn new(select: &'a S, bank: B, register: &'a R) -> Self {
+        Banked { select, bank, register }
+    }
+
+    /// Selects the bank
+    ///
+    /// The bank stays selected, as far as interrupt handlers are concerned,
+    /// until the critical section ends.
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    pub unsafe fn select(&self, _cs: &CriticalSection) {
+        self.select.write(self.bank)
+    }
+
+    /// Selects the bank, then reads the value of the register
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because selecting the bank is side effectful
+    #[inline(always)]
+    pub unsafe fn read<T>(&self, cs: &CriticalSection) -> T
+        where R: Read<T>, T: Copy
+    {
+        self.select(cs);
+        self.register.read()
+    }
+
+    /// Selects the bank, then writes `value` into the register
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    pub unsafe fn write<T>(&self, cs: &CriticalSection, value: T)
+        where R: Write<T>, T: Copy
+    {
+        self.select(cs);
+        self.register.write(value)
+    }
+
+    /// Selects the bank, then performs a read-modify-write operation on the
+    /// register
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    pub unsafe fn modify<T, F>(&self, cs: &CriticalSection, f: F)
+        where R: Read<T> + Write<T>, T: Copy, F: FnOnce(T) -> T
+    {
+        self.select(cs);
+        self.register.write(f(self.register.read()))
+    }
+}
@@ -0,0 +1,138 @@
+//! Memory barriers
+//!
+//! Portable barriers that lower to the right instruction for each target.
+//! Every ordered operation of this crate, like
+//! [`Doorbell::ring`](../struct.Doorbell.html#method.ring), is built on
+//! them.
+//!
+//! | Target              | `full`                 | `write`              | `complete`             |
+//! |---------------------|------------------------|----------------------|------------------------|
+//! | ARMv6-M, ARMv7+     | `dmb`                  | `dmb` (`dmb st`[^1]) | `dsb`                  |
+//! | AArch64             | `dmb sy`               | `dmb st`             | `dsb sy`               |
+//! | RISC-V              | `fence iorw, iorw`     | `fence ow, ow`       | `fence iorw, iorw`     |
+//! | x86, x86_64[^2]     | `mfence`               | `sfence`             | `mfence`               |
+//! | Xtensa              | `memw`                 | `memw`               | `memw`                 |
+//! | MSP430              | compiler fence         | compiler fence       | compiler fence         |
+//! | others              | `fence(SeqCst)`        | `fence(SeqCst)`      | `fence(SeqCst)`        |
+//!
+//! [^1]: `dmb st` on A- and R-profile cores; M-profile cores only implement
+//! the full system `dmb`.
+//!
+//! [^2]: `mfence` needs SSE2 and `sfence` SSE; x86 targets without them
+//! use `fence(SeqCst)` instead.
+//!
+//! Xtensa cores let loads bypass the stores sitting in the write buffer,
+//! even when both target device memory; `memw` is the only barrier they
+//! have.
+//!
+//! MSP430 cores never reorder memory accesses, so ordering the accesses in
+//! the instruction stream is enough.
+//!
+//! All barriers are also compiler fences.
+
+#[cfg(not(any(all(target_arch = "arm", any(target_feature = "v7", target_feature = "mclass")),
+              target_arch = "aarch64",
+              target_arch = "riscv32",
+              target_arch = "riscv64",
+              all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"),
+              target_arch = "xtensa")))]
+use core::sync::atomic::{self, Ordering};
+
+#[cfg(any(all(target_arch = "arm", any(target_feature = "v7", target_feature = "mclass")),
+          target_arch = "aarch64",
+          target_arch = "riscv32",
+          target_arch = "riscv64",
+          all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse"),
+          target_arch = "xtensa"))]
+macro_rules! barrier {
+    ($instruction:literal) => {
+        unsafe { core::arch::asm!($instruction, options(nostack, preserves_flags)) }
+    }
+}
+
+/// Orders every memory and device access that precedes the barrier before
+/// every access that follows it
+#[inline(always)]
+pub fn full() {
+    #[cfg(all(target_arch = "arm", any(target_feature = "v7", target_feature = "mclass")))]
+    barrier!("dmb");
+    #[cfg(target_arch = "aarch64")]
+    barrier!("dmb sy");
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    barrier!("fence iorw, iorw");
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"))]
+    barrier!("mfence");
+    #[cfg(target_arch = "xtensa")]
+    barrier!("memw");
+    #[cfg(target_arch = "msp430")]
+    atomic::compiler_fence(Ordering::SeqCst);
+    #[cfg(not(any(all(target_arch = "arm", any(target_feature = "v7", target_feature = "mclass")),
+                  target_arch = "aarch64",
+                  target_arch = "riscv32",
+                  target_arch = "riscv64",
+                  all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"),
+                  target_arch = "xtensa",
+                  target_arch = "msp430")))]
+    atomic::fence(Ordering::SeqCst);
+}
+
+/// Orders the writes that precede the barrier before the writes that
+/// follow it
+///
+/// Enough to make data written to RAM visible to a device before the write
+/// that tells the device to fetch it.
+#[inline(always)]
+pub fn write() {
+    #[cfg(all(target_arch = "arm", target_feature = "mclass"))]
+    barrier!("dmb");
+    #[cfg(all(target_arch = "arm", target_feature = "v7", not(target_feature = "mclass")))]
+    barrier!("dmb st");
+    #[cfg(target_arch = "aarch64")]
+    barrier!("dmb st");
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    barrier!("fence ow, ow");
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse"))]
+    barrier!("sfence");
+    #[cfg(target_arch = "xtensa")]
+    barrier!("memw");
+    #[cfg(target_arch = "msp430")]
+    atomic::compiler_fence(Ordering::SeqCst);
+    #[cfg(not(any(all(target_arch = "arm", any(target_feature = "v7", target_feature = "mclass")),
+                  target_arch = "aarch64",
+                  target_arch = "riscv32",
+                  target_arch = "riscv64",
+                  all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse"),
+                  target_arch = "xtensa",
+                  target_arch = "msp430")))]
+    atomic::fence(Ordering::SeqCst);
+}
+
+/// Waits until every memory and device access that precedes the barrier has
+/// completed
+///
+/// Stronger than [`full`]: no instruction that follows the barrier executes
+/// before the accesses complete, which matters before changing the
+/// processor's state, e.g. before `wfi` or after disabling a clock.
+#[inline(always)]
+pub fn complete() {
+    #[cfg(all(target_arch = "arm", any(target_feature = "v7", target_feature = "mclass")))]
+    barrier!("dsb");
+    #[cfg(target_arch = "aarch64")]
+    barrier!("dsb sy");
+    #[cfg(any(target_arch = "riscv32", target_arch = "riscv64"))]
+    barrier!("fence iorw, iorw");
+    #[cfg(all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"))]
+    barrier!("mfence");
+    #[cfg(target_arch = "xtensa")]
+    barrier!("memw");
+    #[cfg(target_arch = "msp430")]
+    atomic::compiler_fence(Ordering::SeqCst);
+    #[cfg(not(any(all(target_arch = "arm", any(target_feature = "v7", target_feature = "mclass")),
+                  target_arch = "aarch64",
+                  target_arch = "riscv32",
+                  target_arch = "riscv64",
+                  all(any(target_arch = "x86", target_arch = "x86_64"), target_feature = "sse2"),
+                  target_arch = "xtensa",
+                  target_arch = "msp430")))]
+    atomic::fence(Ordering::SeqCst);
+}
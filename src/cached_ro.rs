@@ -0,0 +1,81 @@
+use core::cell::Cell;
+
+use Read;
+
+/// Read-only view of a register that caches the value read
+///
+/// For registers that are expensive to read but rarely change, like ID,
+/// calibration or trim registers behind a slow bridge: the hardware is read
+/// once, and later reads are served from RAM until the cache is
+/// [`invalidate`](#method.invalidate)d.
+///
+/// ```
+/// use volatile_register::{CachedRO, RO};
+///
+/// let mut memory = 0x0413u32;
+/// let memory = &mut memory as *mut u32;
+/// let idcode = unsafe { &*(memory as *const RO<u32>) };
+/// let cached = CachedRO::new(idcode);
+///
+/// assert_eq!(cached.read(), 0x0413);
+/// unsafe { *memory = 0x0415 }
+/// assert_eq!(cached.read(), 0x0413);
+/// assert_eq!(cached.read_uncached(), 0x0415);
+/// cached.invalidate();
+/// assert_eq!(cached.read(), 0x0415);
+/// ```
+pub struct CachedRO<'a, T>
+    where T: Copy
+{
+    register: &'a dyn Read<T>,
+    cache: Cell<Option<T>>,
+}
+
+impl<'a, T> CachedRO<'a, T>
+    where T: Copy
+{
+    /// Creates an empty cache in front of `register`
+    pub fn new<R>(register: &'a R) -> Self
+        where R: Read<T>
+    {
+        CachedRO { register, cache: Cell::new(None) }
+    }
+
+    /// Returns the cached value, reading the register if the cache is empty
+    #[inline]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub fn read(&self) -> T {
+        match self.cache.get() {
+            Some(value) => value,
+            None => {
+                let value = self.register.read();
+                self.cache.set(Some(value));
+                value
+            }
+        }
+    }
+
+    /// Reads the register, bypassing the cache
+    ///
+    /// The cache is left untouched.
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub fn read_uncached(&self) -> T {
+        self.register.read()
+    }
+
+    /// Empties the cache, so the next `read` reads the register
+    pub fn invalidate(&self) {
+        self.cache.set(None)
+    }
+}
+
+impl<'a, T> Read<T> for CachedRO<'a, T>
+    where T: Copy
+{
+    #[inline]
+    #[cfg_attr(feature = "timing", track_caller)]
+    fn read(&self) -> T {
+        CachedRO::read(self)
+    }
+}
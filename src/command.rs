@@ -0,0 +1,120 @@
+use core::marker::PhantomData;
+
+use poll::{Backoff, Timeout};
+use {Int, Read, Write};
+
+/// Command register paired with the status register that acknowledges the
+/// commands
+///
+/// PLLs, flash controllers and mailboxes take commands through a write-only
+/// register and set an acknowledge bit in a status register once they're
+/// done. `issue` writes a request of type `Q`, then waits for the bits
+/// selected by the `ack` mask to be set, and returns the status value that
+/// acknowledged it, which often carries the response.
+///
+/// ```
+/// use volatile_register::poll::{Spin, Timeout};
+/// use volatile_register::{Command, RO, WO};
+///
+/// #[repr(C)]
+/// pub struct Flash {
+///     pub cmd: WO<u32>,
+///     pub status: RO<u32>,
+/// }
+///
+/// pub enum FlashCommand {
+///     Erase = 0x20,
+///     Program = 0x02,
+/// }
+///
+/// impl From<FlashCommand> for u32 {
+///     fn from(command: FlashCommand) -> u32 {
+///         command as u32
+///     }
+/// }
+///
+/// const DONE: u32 = 1 << 31;
+///
+/// let mut memory = [0u32; 2];
+/// let flash = unsafe { &*(memory.as_mut_ptr() as *const Flash) };
+/// let command = Command::<_, _, FlashCommand, u32>::new(&flash.cmd, &flash.status, DONE);
+///
+/// // nothing acknowledges the command in RAM
+/// let result = unsafe { command.issue(FlashCommand::Erase, 100, &mut Spin) };
+/// assert_eq!(result, Err(Timeout { last: Some(0) }));
+/// assert_eq!(memory[0], 0x20);
+/// ```
+pub struct Command<'a, C, S, Q, A>
+    where C: 'a, S: 'a
+{
+    command: &'a C,
+    status: &'a S,
+    ack: A,
+    _request: PhantomData<fn(Q)>,
+}
+
+impl<'a, C, S, Q, A> Command<'a, C, S, Q, A>
+    where S: Read<A>, A: Int
+{
+    /// Pairs the `command` register with the `status` register, whose bits
+    /// selected by `ack` are set once a command has been carried out
+    pub fn new(command: &'a C, status: &'a S, ack: A) -> Self {
+        Command { command, status, ack, _request: PhantomData }
+    }
+
+    /// Writes `request` into the command register, then waits for it to be
+    /// acknowledged
+    ///
+    /// Polls the status register at most `max_attempts` times, calling
+    /// `backoff` in between, like [`Read::wait_until`](trait.Read.html#method.wait_until),
+    /// and returns the value that had the acknowledge bits set.
+    ///
+    /// The acknowledge bits must be clear when the command is written: a
+    /// bit left set by the previous command, on devices that only clear it
+    /// when software does, is taken as the acknowledge of this one, and
+    /// `issue` returns before the command completes. Clear it beforehand,
+    /// through the register that does so on such devices.
+    ///
+    /// ```
+    /// use std::cell::Cell;
+    ///
+    /// use volatile_register::poll::Spin;
+    /// use volatile_register::{Command, Read, WO};
+    ///
+    /// const DONE: u32 = 1 << 31;
+    ///
+    /// // acknowledges on the third poll, with the response in the low byte
+    /// struct Status {
+    ///     polls: Cell<usize>,
+    /// }
+    ///
+    /// impl Read<u32> for Status {
+    ///     fn read(&self) -> u32 {
+    ///         self.polls.set(self.polls.get() + 1);
+    ///         if self.polls.get() == 3 { DONE | 0x5a } else { 0 }
+    ///     }
+    /// }
+    ///
+    /// let mut memory = 0u32;
+    /// let cmd = unsafe { &*(&mut memory as *mut u32 as *const WO<u32>) };
+    /// let status = Status { polls: Cell::new(0) };
+    /// let command = Command::<_, _, u32, u32>::new(cmd, &status, DONE);
+    ///
+    /// assert_eq!(unsafe { command.issue(0x9f, 10, &mut Spin) }, Ok(DONE | 0x5a));
+    /// assert_eq!(status.polls.get(), 3);
+    /// assert_eq!(memory, 0x9f);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline]
+    pub unsafe fn issue<W, B>(&self, request: Q, max_attempts: usize, backoff: &mut B)
+                              -> Result<A, Timeout<A>>
+        where C: Write<W>, Q: Into<W>, W: Copy, B: Backoff
+    {
+        self.command.write(request.into());
+        let ack = self.ack;
+        self.status.wait_until(|status| status & ack == ack, max_attempts, backoff)
+    }
+}
@@ -0,0 +1,114 @@
+//! Accesses ordered with compiler fences only
+//!
+//! A volatile access is never reordered with other volatile accesses, but the
+//! compiler is free to move ordinary memory accesses, like writes to a DMA
+//! buffer, across it. The `*_compiler_fenced` methods put a compiler fence on
+//! each side of the access, which pins the surrounding memory accesses in
+//! program order without emitting any instruction.
+//!
+//! They do *not* order the accesses as seen by other bus masters: the
+//! processor and its write buffers may still reorder them. Only use them
+//! where the hardware already keeps accesses in order, like tightly-coupled
+//! memory or strongly-ordered device regions; elsewhere use the methods that
+//! issue a hardware fence, like [`Doorbell::ring`](../struct.Doorbell.html#method.ring).
+
+use core::sync::atomic::{self, Ordering};
+
+use {raw, Doorbell, RO, RW, WO};
+
+impl<T> RO<T>
+    where T: Copy
+{
+    /// Reads the value of the register, with a compiler fence before and
+    /// after the read
+    ///
+    /// See the [`compiler_fenced`](compiler_fenced/index.html) module for
+    /// when this is enough.
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub fn read_compiler_fenced(&self) -> T {
+        atomic::compiler_fence(Ordering::SeqCst);
+        let value = self.read();
+        atomic::compiler_fence(Ordering::SeqCst);
+        value
+    }
+}
+
+impl<T> RW<T>
+    where T: Copy
+{
+    /// Reads the value of the register, with a compiler fence before and
+    /// after the read
+    ///
+    /// See the [`compiler_fenced`](compiler_fenced/index.html) module for
+    /// when this is enough.
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub fn read_compiler_fenced(&self) -> T {
+        atomic::compiler_fence(Ordering::SeqCst);
+        let value = self.read();
+        atomic::compiler_fence(Ordering::SeqCst);
+        value
+    }
+
+    /// Writes a `value` into the register, with a compiler fence before and
+    /// after the write
+    ///
+    /// See the [`compiler_fenced`](compiler_fenced/index.html) module for
+    /// when this is enough.
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn write_compiler_fenced(&self, value: T) {
+        atomic::compiler_fence(Ordering::SeqCst);
+        self.write(value);
+        atomic::compiler_fence(Ordering::SeqCst);
+    }
+}
+
+impl<T> WO<T>
+    where T: Copy
+{
+    /// Writes a `value` into the register, with a compiler fence before and
+    /// after the write
+    ///
+    /// See the [`compiler_fenced`](compiler_fenced/index.html) module for
+    /// when this is enough.
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn write_compiler_fenced(&self, value: T) {
+        atomic::compiler_fence(Ordering::SeqCst);
+        self.write(value);
+        atomic::compiler_fence(Ordering::SeqCst);
+    }
+}
+
+impl<T> Doorbell<T>
+    where T: Copy
+{
+    /// Like [`ring`](#method.ring), but with a compiler fence instead of a
+    /// hardware fence before the write
+    ///
+    /// Preceding writes are emitted before the doorbell, but nothing keeps
+    /// the processor from reordering them. Only correct when the data and
+    /// the doorbell live in memory that the hardware keeps in order; see the
+    /// [`compiler_fenced`](compiler_fenced/index.html) module.
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn ring_compiler_fenced(&self, value: T) {
+        atomic::compiler_fence(Ordering::SeqCst);
+        raw::write(self.as_ptr(), value);
+        atomic::compiler_fence(Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,60 @@
+//! Atomic read-modify-write through the Xtensa `S32C1I` conditional store
+//!
+//! `S32C1I` stores a value only if the word in memory still equals the
+//! `SCOMPARE1` special register, and returns the word it found either way.
+//! Retrying until the word didn't change between the read and the store
+//! makes the whole update atomic with respect to interrupt handlers and the
+//! other core, without masking interrupts.
+//!
+//! Only Xtensa cores with the Conditional Store option have it, like the
+//! ESP32 and ESP32-S3 (`target_has_atomic = "32"`); the ESP32-S2 doesn't.
+
+use {raw, RW};
+
+impl RW<u32> {
+    /// Performs a read-modify-write operation that is atomic with respect to
+    /// the other bus masters, and returns the value `f` was applied to
+    ///
+    /// `f` is called again, on the new value, whenever the register changed
+    /// between the read and the conditional store.
+    ///
+    /// ``` no_run
+    /// use volatile_register::RW;
+    ///
+    /// // flags shared with the interrupt handlers of both cores
+    /// let flags = unsafe { &*(0x3FFB_0000 as *const RW<u32>) };
+    ///
+    /// // a handler can set other flags meanwhile without losing either
+    /// // update
+    /// let previous = unsafe { flags.modify_atomic(|flags| flags | 1 << 5) };
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful; the
+    /// register must also be on a bus that implements the conditional store,
+    /// as configured by the `ATOMCTL` register of the core (see the
+    /// technical reference manual of the chip)
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn modify_atomic<F>(&self, mut f: F) -> u32
+        where F: FnMut(u32) -> u32
+    {
+        let register = self.register.as_ptr();
+        let mut current = raw::read(register);
+        loop {
+            let expected = current;
+            // replaced with the value found in the register
+            current = f(expected);
+            core::arch::asm!("wsr.scompare1 {expected}",
+                             "s32c1i {current}, {register}, 0",
+                             expected = in(reg) expected,
+                             current = inout(reg) current,
+                             register = in(reg) register,
+                             options(nostack, preserves_flags));
+            if current == expected {
+                return expected;
+            }
+        }
+    }
+}
@@ -0,0 +1,172 @@
+//! Machine-readable register map descriptions
+//!
+//! Every block defined with [`register_block!`](../macro.register_block.html)
+//! gets a `DESCRIPTION` constant: its [`REGISTERS`] encoded in the format
+//! below, evaluated at compile time. Host tools can decode it to generate SVD
+//! or IP-XACT files, keeping the Rust definition as the single source of
+//! truth for the register map.
+//!
+//! With the `export-map` feature enabled the descriptions are also kept in
+//! the compiled binary, in the `.volatile_register.map` section (ELF
+//! targets), where tools can extract them without running any code:
+//!
+//! ``` text
+//! $ objcopy -O binary --only-section=.volatile_register.map firmware.elf map.bin
+//! ```
+//!
+//! # Format
+//!
+//! All integers are little-endian, strings are UTF-8 prefixed by their
+//! length in bytes as a `u16`.
+//!
+//! | Field            | Encoding                                 |
+//! |------------------|------------------------------------------|
+//! | magic            | `b"VRMAP"`                               |
+//! | version          | `u8`, currently `1`                      |
+//! | block name       | string                                   |
+//! | register count   | `u16`                                    |
+//! | registers        | one record per register, in offset order |
+//!
+//! Each register record:
+//!
+//! | Field  | Encoding                                                          |
+//! |--------|-------------------------------------------------------------------|
+//! | name   | string                                                            |
+//! | offset | `u32`                                                             |
+//! | size   | `u32`                                                             |
+//! | width  | `u32`                                                             |
+//! | access | `u8`: `0` reserved, `1` read-only, `2` read-write, `3` write-only |
+//! | reset  | `u8` flag, `1` if followed by a `u64` reset value, `0` otherwise  |
+//!
+//! [`REGISTERS`]: ../trait.RegisterBlock.html#associatedconstant.REGISTERS
+//!
+//! ```
+//! #[macro_use]
+//! extern crate volatile_register;
+//!
+//! use volatile_register::{RO, RW};
+//!
+//! register_block! {
+//!     pub struct Rng {
+//!         pub cr: RW<u32> = 0,
+//!         pub dr: RO<u32>,
+//!     }
+//! }
+//!
+//! fn main() {
+//!     assert_eq!(&Rng::DESCRIPTION[..11], b"VRMAP\x01\x03\x00Rng");
+//!     // header, two records, one reset value
+//!     assert_eq!(Rng::DESCRIPTION.len(), 13 + 2 * (2 + 2 + 12 + 1 + 1) + 8);
+//! }
+//! ```
+
+use access::Kind;
+use RegisterInfo;
+
+/// Leading bytes of a description
+pub const MAGIC: [u8; 5] = *b"VRMAP";
+/// Version of the format produced by this crate
+pub const VERSION: u8 = 1;
+
+/// Returns the size of the description of the block `name`
+pub const fn len(name: &str, registers: &[RegisterInfo]) -> usize {
+    let mut len = MAGIC.len() + 1 + 2 + name.len() + 2;
+    let mut i = 0;
+    while i < registers.len() {
+        let register = &registers[i];
+        len += 2 + register.name.len() + 4 + 4 + 4 + 1 + 1;
+        if register.reset.is_some() {
+            len += 8;
+        }
+        i += 1;
+    }
+    len
+}
+
+/// Encodes the description of the block `name`
+///
+/// # Panics
+///
+/// If `N` is not [`len(name, registers)`](fn.len.html). In `const` contexts
+/// that's a compile time error.
+pub const fn encode<const N: usize>(name: &str, registers: &[RegisterInfo]) -> [u8; N] {
+    assert!(N == len(name, registers), "wrong description size");
+
+    let mut out = [0; N];
+    let mut at = put(&mut out, 0, &MAGIC);
+    out[at] = VERSION;
+    at += 1;
+    at = put_str(&mut out, at, name);
+    at = put(&mut out, at, &(registers.len() as u16).to_le_bytes());
+
+    let mut i = 0;
+    while i < registers.len() {
+        let register = &registers[i];
+        at = put_str(&mut out, at, register.name);
+        at = put(&mut out, at, &(register.offset as u32).to_le_bytes());
+        at = put(&mut out, at, &(register.size as u32).to_le_bytes());
+        at = put(&mut out, at, &(register.width as u32).to_le_bytes());
+        out[at] = match register.access {
+            None => 0,
+            Some(Kind::ReadOnly) => 1,
+            Some(Kind::ReadWrite) => 2,
+            Some(Kind::WriteOnly) => 3,
+        };
+        at += 1;
+        match register.reset {
+            Some(reset) => {
+                out[at] = 1;
+                at = put(&mut out, at + 1, &reset.to_le_bytes());
+            }
+            None => {
+                out[at] = 0;
+                at += 1;
+            }
+        }
+        i += 1;
+    }
+    out
+}
+
+const fn put<const N: usize>(out: &mut [u8; N], at: usize, bytes: &[u8]) -> usize {
+    let mut i = 0;
+    while i < bytes.len() {
+        out[at + i] = bytes[i];
+        i += 1;
+    }
+    at + bytes.len()
+}
+
+const fn put_str<const N: usize>(out: &mut [u8; N], at: usize, s: &str) -> usize {
+    let at = put(out, at, &(s.len() as u16).to_le_bytes());
+    put(out, at, s.as_bytes())
+}
+
+/// Keeps a description in the `.volatile_register.map` section
+#[cfg(feature = "export-map")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __export_map {
+    ($name:ident) => {
+        const _: () = {
+            #[used]
+            #[cfg_attr(not(any(target_os = "macos", target_os = "ios", target_os = "windows")),
+                       link_section = ".volatile_register.map")]
+            static MAP: [u8; $name::DESCRIPTION.len()] = $name::DESCRIPTION;
+        };
+    }
+}
+
+#[cfg(not(feature = "export-map"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __export_map {
+    ($name:ident) => {}
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __reset {
+    () => { None };
+    ($reset:expr) => { Some(($reset) as u64) };
+}
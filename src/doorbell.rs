@@ -0,0 +1,195 @@
+use core::cell::UnsafeCell;
+use core::mem::{self, MaybeUninit};
+
+use access::{HasAccess, Kind};
+use {barrier, raw, Write};
+
+/// Doorbell register
+///
+/// Write-only register used to notify a device that work is available, for
+/// example that DMA descriptors have been written to RAM. `ring` issues a
+/// fence before the store, so that the device never observes the doorbell
+/// before the data it refers to.
+///
+/// Like [`WO`](struct.WO.html), a `Doorbell` never assumes that it holds an
+/// initialized value.
+///
+/// ``` no_run
+/// use volatile_register::Doorbell;
+///
+/// #[repr(C)]
+/// pub struct Dma {
+///     pub tx_kick: Doorbell<u32>,
+/// }
+///
+/// static mut DESCRIPTORS: [u32; 4] = [0; 4];
+///
+/// let dma = unsafe { &*(0x4000_2000 as *const Dma) };
+/// unsafe {
+///     DESCRIPTORS[0] = 0x8000_0040;
+///     dma.tx_kick.ring(1);
+/// }
+/// ```
+#[repr(transparent)]
+pub struct Doorbell<T>
+    where T: Copy
+{
+    register: UnsafeCell<MaybeUninit<T>>,
+}
+
+impl<T> Doorbell<T>
+    where T: Copy
+{
+    /// Returns the address of the register
+    #[inline(always)]
+    pub fn addr(&self) -> usize {
+        self as *const Doorbell<T> as usize
+    }
+
+    /// Makes all preceding memory writes visible, then writes `value` into
+    /// the register
+    ///
+    /// Recording the writes through an `indirect` vtable, with the
+    /// `indirect` feature:
+    ///
+    /// ```
+    /// # #[cfg(feature = "indirect")]
+    /// # fn main() {
+    /// use std::sync::Mutex;
+    ///
+    /// use volatile_register::indirect::{self, Vtable};
+    /// use volatile_register::Doorbell;
+    ///
+    /// #[repr(C)]
+    /// pub struct Dma {
+    ///     pub ctrl: u32,
+    ///     pub tx_kick: Doorbell<u32>,
+    /// }
+    ///
+    /// // (address, bytes written)
+    /// static WRITES: Mutex<Vec<(usize, Vec<u8>)>> = Mutex::new(Vec::new());
+    ///
+    /// fn read(_: usize, _: &mut [u8]) {
+    ///     unreachable!("`ring` doesn't read the doorbell")
+    /// }
+    ///
+    /// fn write(address: usize, data: &[u8]) {
+    ///     WRITES.lock().unwrap().push((address, data.to_vec()));
+    /// }
+    ///
+    /// static VTABLE: Vtable = Vtable { read, write };
+    ///
+    /// let mut memory = [0u32; 2];
+    /// let base = memory.as_mut_ptr() as usize;
+    /// let dma = unsafe { &*(base as *const Dma) };
+    ///
+    /// unsafe { indirect::install(&VTABLE) }
+    /// unsafe { dma.tx_kick.ring(3) }
+    /// indirect::uninstall();
+    ///
+    /// assert_eq!(dma.tx_kick.addr(), base + 4);
+    /// assert_eq!(*WRITES.lock().unwrap(), [(base + 4, 3u32.to_ne_bytes().to_vec())]);
+    /// # }
+    /// # #[cfg(not(feature = "indirect"))]
+    /// # fn main() {}
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn ring(&self, value: T) {
+        barrier::write();
+        raw::write(self.as_ptr(), value)
+    }
+
+    /// Returns a raw pointer to the register
+    pub(crate) fn as_ptr(&self) -> *mut T {
+        self.register.get() as *mut T
+    }
+}
+
+impl<T> HasAccess for Doorbell<T>
+    where T: Copy
+{
+    const ACCESS: Option<Kind> = Some(Kind::WriteOnly);
+    const WIDTH: usize = mem::size_of::<T>();
+}
+
+/// Writes `value` into the `payload` register, then `kick` into the
+/// `doorbell` register
+///
+/// A fence between the two stores guarantees that the payload is visible to
+/// the device before the doorbell, even when the two registers sit behind
+/// different buses or write buffers.
+///
+/// ``` no_run
+/// use volatile_register::{write_then_notify, WO};
+///
+/// #[repr(C)]
+/// pub struct Mailbox {
+///     pub data: WO<u32>,
+///     pub notify: WO<u32>,
+/// }
+///
+/// let mailbox = unsafe { &*(0x4001_0000 as *const Mailbox) };
+/// unsafe { write_then_notify(&mailbox.data, 0xc0ffee, &mailbox.notify, 1) }
+/// ```
+///
+/// Recording the accesses through an `indirect` vtable, with the `indirect`
+/// feature:
+///
+/// ```
+/// # #[cfg(feature = "indirect")]
+/// # fn main() {
+/// use std::sync::Mutex;
+///
+/// use volatile_register::indirect::{self, Vtable};
+/// use volatile_register::{write_then_notify, WO};
+///
+/// #[repr(C)]
+/// pub struct Mailbox {
+///     pub data: WO<u32>,
+///     pub notify: WO<u32>,
+/// }
+///
+/// // (address, write)
+/// static ACCESSES: Mutex<Vec<(usize, bool)>> = Mutex::new(Vec::new());
+///
+/// fn read(address: usize, _: &mut [u8]) {
+///     ACCESSES.lock().unwrap().push((address, false));
+/// }
+///
+/// fn write(address: usize, _: &[u8]) {
+///     ACCESSES.lock().unwrap().push((address, true));
+/// }
+///
+/// static VTABLE: Vtable = Vtable { read, write };
+///
+/// let mut memory = [0u32; 2];
+/// let base = memory.as_mut_ptr() as usize;
+/// let mailbox = unsafe { &*(base as *const Mailbox) };
+///
+/// unsafe { indirect::install(&VTABLE) }
+/// unsafe { write_then_notify(&mailbox.data, 0xc0ffee, &mailbox.notify, 1) }
+/// indirect::uninstall();
+///
+/// // payload first, then the doorbell, and nothing read
+/// assert_eq!(*ACCESSES.lock().unwrap(), [(base, true), (base + 4, true)]);
+/// # }
+/// # #[cfg(not(feature = "indirect"))]
+/// # fn main() {}
+/// ```
+///
+/// # Safety
+///
+/// `unsafe` because writes to a register are side effectful
+#[inline(always)]
+pub unsafe fn write_then_notify<P, T, D, K>(payload: &P, value: T, doorbell: &D, kick: K)
+    where P: Write<T>, T: Copy, D: Write<K>, K: Copy
+{
+    payload.write(value);
+    barrier::write();
+    doorbell.write(kick);
+}
@@ -0,0 +1,143 @@
+//! Register dumps for crash handlers
+//!
+//! Register blocks installed at initialization can be dumped later by a
+//! panic or fault handler, when the drivers that own them are out of reach.
+//! Only [`Pure`](../struct.Pure.html) registers are read, so dumping never
+//! changes the state of a peripheral; the other registers are skipped. The
+//! blocks have to be declared with
+//! [`register_block!`](../macro.register_block.html).
+//!
+//! The table of blocks is installed with a
+//! [`TakeOnce`](../struct.TakeOnce.html) flag: `install`, `blocks` and
+//! `dump_all` only exist on the targets where `TakeOnce` does. `Block::dump`
+//! is available everywhere.
+//!
+//! ```
+//! #[macro_use]
+//! extern crate volatile_register;
+//!
+//! use volatile_register::dump::{self, Block};
+//! use volatile_register::{Pure, RO, RW};
+//!
+//! register_block! {
+//!     pub struct Uart {
+//!         pub cr: Pure<RW<u32>>,
+//!         // reading the data register pops the RX FIFO
+//!         pub dr: RO<u32>,
+//!         pub sr: Pure<RO<u16>>,
+//!     }
+//! }
+//!
+//! fn main() {
+//!     let mut memory = [0x0000_0301_u32, 0x55, 0x80];
+//!     let base = memory.as_mut_ptr() as usize;
+//!
+//!     // `Block`s are usually `static`s, with the addresses from the datasheet
+//!     let blocks: &'static [Block] = Box::leak(Box::new([
+//!         unsafe { Block::new::<Uart>("uart0", base) },
+//!     ]));
+//!     dump::install(blocks).unwrap();
+//!
+//!     // in the panic handler, with a serial port as the sink
+//!     let mut sink = String::new();
+//!     dump::dump_all(&mut sink).unwrap();
+//!     assert_eq!(sink, format!("uart0 @ {:#x}\n  cr = 0x00000301\n  sr = 0x0080\n", base));
+//! }
+//! ```
+
+use core::fmt;
+
+cfg_take_once! { use registry::Registry; }
+use RegisterBlock;
+
+cfg_take_once! { pub use registry::AlreadyInstalled; }
+
+/// Register block that can be dumped
+pub struct Block {
+    name: &'static str,
+    address: usize,
+    dump: fn(usize, &mut dyn fmt::Write) -> fmt::Result,
+}
+
+impl Block {
+    /// Describes the block called `name`, with the `B` layout, starting at
+    /// `address`
+    ///
+    /// # Safety
+    ///
+    /// The registers of `B` must be found at `address` whenever the block
+    /// is dumped
+    pub const unsafe fn new<B>(name: &'static str, address: usize) -> Self
+        where B: RegisterBlock
+    {
+        Block { name, address, dump: registers::<B> }
+    }
+
+    /// Returns the name of the block
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Writes the name and the address of the block into `sink`, followed by
+    /// one line per `Pure` register
+    pub fn dump<W>(&self, sink: &mut W) -> fmt::Result
+        where W: fmt::Write
+    {
+        writeln!(sink, "{} @ {:#x}", self.name, self.address)?;
+        (self.dump)(self.address, sink)
+    }
+}
+
+fn registers<B>(address: usize, sink: &mut dyn fmt::Write) -> fmt::Result
+    where B: RegisterBlock
+{
+    let block = unsafe { &*(address as *const B) };
+    for register in block.iter_registers() {
+        let value = match register.peek() {
+            Some(value) => value,
+            None => continue,
+        };
+        let digits = 2 + 2 * register.info().width;
+        match register.index() {
+            Some(index) => {
+                writeln!(sink, "  {}[{}] = {:#0w$x}", register.name(), index, value, w = digits)?
+            }
+            None => writeln!(sink, "  {} = {:#0w$x}", register.name(), value, w = digits)?,
+        }
+    }
+    Ok(())
+}
+
+cfg_take_once! { static BLOCKS: Registry<Block> = Registry::new(); }
+
+cfg_take_once! {
+    /// Makes `blocks` the blocks dumped by [`dump_all`]
+    ///
+    /// The table can only be installed once: later calls fail with
+    /// `AlreadyInstalled`, and keep the first table.
+    pub fn install(blocks: &'static [Block]) -> Result<(), AlreadyInstalled> {
+        BLOCKS.install(blocks)
+    }
+}
+
+cfg_take_once! {
+    /// Returns the installed blocks, in table order
+    pub fn blocks() -> &'static [Block] {
+        BLOCKS.get()
+    }
+}
+
+cfg_take_once! {
+    /// Dumps every installed block into `sink`, in table order
+    ///
+    /// Suitable for panic and fault handlers: it doesn't allocate, and it
+    /// only performs side-effect-free reads.
+    pub fn dump_all<W>(sink: &mut W) -> fmt::Result
+        where W: fmt::Write
+    {
+        for block in blocks() {
+            block.dump(sink)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,366 @@
+use {Int, RO, RW};
+
+/// Bit field of a register
+///
+/// Usually defined with the [`fields!`](macro.fields.html) macro.
+///
+/// ```
+/// use volatile_register::{Field, FieldValue};
+///
+/// const MODE: Field<u32> = Field::new(0b111 << 4, 4);
+/// const TURBO: FieldValue<u32> = MODE.value(7);
+///
+/// assert_eq!(MODE.try_set(0, 7), Ok(0x70));
+/// assert!(MODE.try_set(0, 8).is_err());
+/// ```
+///
+/// Values that don't fit in the field are rejected at compile time:
+///
+/// ``` compile_fail
+/// use volatile_register::{Field, FieldValue};
+///
+/// const MODE: Field<u32> = Field::new(0b111 << 4, 4);
+/// const INVALID: FieldValue<u32> = MODE.value(8);
+/// # fn main() { let _ = INVALID; }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Field<T> {
+    mask: T,
+    offset: u32,
+}
+
+impl<T> Field<T>
+    where T: Int
+{
+    /// Creates the field that occupies the bits set in `mask`, the lowest of
+    /// which is bit `offset`
+    pub const fn new(mask: T, offset: u32) -> Self {
+        Field { mask, offset }
+    }
+
+    /// Returns the bits the field occupies in the register
+    pub fn mask(self) -> T {
+        self.mask
+    }
+
+    /// Returns the position of the lowest bit of the field
+    pub fn offset(self) -> u32 {
+        self.offset
+    }
+
+    /// Extracts the value of the field from `bits`, the value of the register
+    pub fn get(self, bits: T) -> T {
+        (bits & self.mask) >> self.offset
+    }
+
+    /// Returns `bits`, the value of the register, with the field set to
+    /// `value`
+    ///
+    /// The bits of `value` that don't fit in the field are discarded.
+    pub fn set(self, bits: T, value: T) -> T {
+        (bits & !self.mask) | ((value << self.offset) & self.mask)
+    }
+
+    /// Like [`set`](#method.set), but returns `Err(FieldOverflow)` instead
+    /// of discarding the bits of `value` that don't fit in the field
+    pub fn try_set(self, bits: T, value: T) -> Result<T, FieldOverflow> {
+        if value & !(self.mask >> self.offset) != T::ZERO {
+            return Err(FieldOverflow);
+        }
+        Ok(self.set(bits, value))
+    }
+
+    /// Returns `bits`, the value of the register, with `delta` added to the
+    /// field, clamping the result to the range of the field
+    ///
+    /// ```
+    /// use volatile_register::Field;
+    ///
+    /// const DUTY: Field<u32> = Field::new(0xf << 4, 4);
+    ///
+    /// assert_eq!(DUTY.saturating_add(0xe1, 3), 0xf1);
+    /// assert_eq!(DUTY.saturating_add(0x21, -3), 0x01);
+    /// ```
+    pub fn saturating_add(self, bits: T, delta: i64) -> T {
+        let max = (self.mask >> self.offset).to_u64() as i128;
+        let value = self.get(bits).to_u64() as i128 + delta as i128;
+        self.set(bits, T::from_u64(value.clamp(0, max) as u64))
+    }
+
+    /// Returns `bits`, the value of the register, with `delta` added to the
+    /// field, wrapping around at the boundaries of the field
+    ///
+    /// The bits outside of the field are never changed.
+    ///
+    /// ```
+    /// use volatile_register::Field;
+    ///
+    /// const DIV: Field<u32> = Field::new(0xf << 4, 4);
+    ///
+    /// assert_eq!(DIV.wrapping_add(0xe1, 3), 0x11);
+    /// assert_eq!(DIV.wrapping_add(0x21, -3), 0xf1);
+    /// ```
+    pub fn wrapping_add(self, bits: T, delta: i64) -> T {
+        let modulus = (self.mask >> self.offset).to_u64() as i128 + 1;
+        let value = self.get(bits).to_u64() as i128 + delta as i128;
+        self.set(bits, T::from_u64(value.rem_euclid(modulus) as u64))
+    }
+}
+
+macro_rules! value {
+    ($($ty:ty,)+) => {
+        $(
+            impl Field<$ty> {
+                /// Names `value` of the field
+                ///
+                /// # Panics
+                ///
+                /// If `value` doesn't fit in the field. In `const` contexts
+                /// that's a compile time error, see [`Field`].
+                pub const fn value(self, value: $ty) -> FieldValue<$ty> {
+                    assert!(value & !(self.mask >> self.offset) == 0,
+                            "value doesn't fit in the field");
+                    FieldValue::new(self, value)
+                }
+            }
+        )+
+    }
+}
+
+value! {
+    u8,
+    u16,
+    u32,
+    u64,
+    usize,
+}
+
+/// Error returned when a value doesn't fit in a [`Field`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FieldOverflow;
+
+/// Named value of a register [`Field`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FieldValue<T> {
+    field: Field<T>,
+    value: T,
+}
+
+impl<T> FieldValue<T>
+    where T: Int
+{
+    /// Names `value` of `field`
+    pub const fn new(field: Field<T>, value: T) -> Self {
+        FieldValue { field, value }
+    }
+
+    /// Returns the field this value belongs to
+    pub fn field(self) -> Field<T> {
+        self.field
+    }
+
+    /// Returns the value, not shifted into place
+    pub fn value(self) -> T {
+        self.value
+    }
+
+    /// Returns `true` if the field has this value in `bits`, the value of
+    /// the register
+    pub fn is_set_in(self, bits: T) -> bool {
+        self.field.get(bits) == self.value
+    }
+}
+
+impl<T> RO<T>
+    where T: Int
+{
+    /// Reads the value of `field`
+    #[inline(always)]
+    pub fn read_field(&self, field: Field<T>) -> T {
+        field.get(self.read())
+    }
+}
+
+impl<T> RW<T>
+    where T: Int
+{
+    /// Reads the value of `field`
+    #[inline(always)]
+    pub fn read_field(&self, field: Field<T>) -> T {
+        field.get(self.read())
+    }
+
+    /// Sets `field` to `value` with a read-modify-write operation
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    pub unsafe fn modify_field(&self, field: Field<T>, value: T) {
+        self.modify(|bits| field.set(bits, value))
+    }
+
+    /// Sets `field` to `value` with a read-modify-write operation, if `value`
+    /// fits in the field
+    ///
+    /// Returns `Err(FieldOverflow)`, without accessing the register, if it
+    /// doesn't.
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    pub unsafe fn try_modify_field(&self, field: Field<T>, value: T) -> Result<(), FieldOverflow> {
+        field.try_set(T::ZERO, value)?;
+        self.modify_field(field, value);
+        Ok(())
+    }
+
+    /// Adds `delta` to `field` with a read-modify-write operation, clamping
+    /// the result to the range of the field
+    ///
+    /// See [`Field::saturating_add`].
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    pub unsafe fn modify_field_saturating(&self, field: Field<T>, delta: i64) {
+        self.modify(|bits| field.saturating_add(bits, delta))
+    }
+
+    /// Adds `delta` to `field` with a read-modify-write operation, wrapping
+    /// around at the boundaries of the field
+    ///
+    /// See [`Field::wrapping_add`].
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    pub unsafe fn modify_field_wrapping(&self, field: Field<T>, delta: i64) {
+        self.modify(|bits| field.wrapping_add(bits, delta))
+    }
+
+    /// Sets a field to the named `value` with a read-modify-write operation
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    pub unsafe fn modify_value(&self, value: FieldValue<T>) {
+        self.modify_field(value.field, value.value)
+    }
+}
+
+/// Defines the bit fields of a register
+///
+/// Each field is declared with its offset and width, in bits, and optionally
+/// a list of named values. The macro expands to a module holding a
+/// [`Field`](struct.Field.html) constant per field, and, for the fields with
+/// named values, a module of the same name holding a
+/// [`FieldValue`](struct.FieldValue.html) constant per value. All of them can
+/// be used in `const` contexts.
+///
+/// The register type must be one of the primitive unsigned integers.
+///
+/// Named values are checked against the width of their field at compile
+/// time, like the values built with [`Field::value`](struct.Field.html#method.value).
+///
+/// ```
+/// #[macro_use]
+/// extern crate volatile_register;
+///
+/// fields! {
+///     /// Fields of the control register
+///     pub mod cr: u32 {
+///         /// Enable
+///         EN: 0, 1,
+///         /// Speed
+///         SPEED: 1, 2 {
+///             /// Low speed
+///             LOW = 0,
+///             /// High speed
+///             HIGH = 3,
+///         },
+///     }
+/// }
+///
+/// # fn main() {
+/// let bits = cr::SPEED::HIGH.field().set(0, cr::SPEED::HIGH.value()) | 1;
+/// assert_eq!(bits, 0b111);
+/// assert_eq!(cr::EN.get(bits), 1);
+/// assert!(cr::SPEED::HIGH.is_set_in(bits));
+/// # }
+/// ```
+///
+/// Fields that don't fit in the register are rejected at compile time:
+///
+/// ``` compile_fail
+/// #[macro_use]
+/// extern crate volatile_register;
+///
+/// fields! {
+///     mod cr: u8 {
+///         PRESCALER: 6, 3,
+///     }
+/// }
+/// # fn main() {}
+/// ```
+///
+/// And so are named values that don't fit in their field:
+///
+/// ``` compile_fail
+/// #[macro_use]
+/// extern crate volatile_register;
+///
+/// fields! {
+///     mod cr: u8 {
+///         MODE: 0, 2 {
+///             FAST = 4,
+///         },
+///     }
+/// }
+/// # fn main() { let _ = cr::MODE::FAST; }
+/// ```
+#[macro_export]
+macro_rules! fields {
+    ($(#[$attr:meta])*
+     $vis:vis mod $module:ident: $ty:ty {
+         $(
+             $(#[$field_attr:meta])*
+             $field:ident: $offset:literal, $width:literal $({
+                 $(
+                     $(#[$value_attr:meta])*
+                     $value:ident = $bits:expr
+                 ),* $(,)?
+             })?
+         ),* $(,)?
+     }) => {
+        $(#[$attr])*
+        #[allow(non_snake_case)]
+        $vis mod $module {
+            $(
+                const _: () = assert!($width > 0 && $offset + $width <= <$ty>::BITS,
+                                      "field doesn't fit in the register");
+
+                $(#[$field_attr])*
+                pub const $field: $crate::Field<$ty> = $crate::Field::new(
+                    (<$ty>::MAX >> (<$ty>::BITS - $width)) << $offset,
+                    $offset,
+                );
+
+                $(
+                    #[doc = concat!("Named values of the `", stringify!($field), "` field")]
+                    pub mod $field {
+                        $(
+                            $(#[$value_attr])*
+                            pub const $value: $crate::FieldValue<$ty> =
+                                super::$field.value($bits);
+                        )*
+                    }
+                )?
+            )*
+        }
+    }
+}
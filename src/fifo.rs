@@ -0,0 +1,142 @@
+use core::marker::PhantomData;
+
+use Read;
+
+/// FIFO: a data register paired with the status register that reports data
+/// availability
+///
+/// ``` no_run
+/// use volatile_register::{Fifo, RO};
+///
+/// #[repr(C)]
+/// pub struct Uart {
+///     pub sr: RO<u32>,
+///     pub dr: RO<u32>,
+/// }
+///
+/// const RXNE: u32 = 1 << 5;
+///
+/// let uart = unsafe { &*(0x4000_4400 as *const Uart) };
+/// let rx = Fifo::new(&uart.sr, &uart.dr);
+///
+/// let mut buffer = [0u8; 16];
+/// let mut len = 0;
+/// for (slot, word) in buffer.iter_mut().zip(rx.drain_while(|sr: u32| sr & RXNE != 0)) {
+///     *slot = word as u8;
+///     len += 1;
+/// }
+/// ```
+pub struct Fifo<'a, S, D>
+    where S: 'a, D: 'a
+{
+    status: &'a S,
+    data: &'a D,
+}
+
+impl<'a, S, D> Fifo<'a, S, D> {
+    /// Pairs the `data` register with its `status` register
+    pub fn new(status: &'a S, data: &'a D) -> Self {
+        Fifo { status, data }
+    }
+
+    /// Returns an iterator that reads the data register as long as
+    /// `available` returns `true` for the value of the status register
+    ///
+    /// The status register is read before every read of the data register.
+    /// The iterator stops at the first status for which `available` returns
+    /// `false`.
+    ///
+    /// ```
+    /// use std::cell::Cell;
+    ///
+    /// use volatile_register::{Fifo, Read};
+    ///
+    /// const RXNE: u32 = 1 << 5;
+    ///
+    /// // An RX FIFO emulated in RAM, holding `received`
+    /// pub struct Sr<'a> {
+    ///     popped: &'a Cell<usize>,
+    ///     received: &'a [u32],
+    /// }
+    ///
+    /// impl<'a> Read<u32> for Sr<'a> {
+    ///     fn read(&self) -> u32 {
+    ///         if self.popped.get() < self.received.len() { RXNE } else { 0 }
+    ///     }
+    /// }
+    ///
+    /// pub struct Dr<'a> {
+    ///     popped: &'a Cell<usize>,
+    ///     received: &'a [u32],
+    /// }
+    ///
+    /// impl<'a> Read<u32> for Dr<'a> {
+    ///     fn read(&self) -> u32 {
+    ///         let popped = self.popped.get();
+    ///         self.popped.set(popped + 1);
+    ///         self.received[popped]
+    ///     }
+    /// }
+    ///
+    /// let popped = Cell::new(0);
+    /// let received = [0x68, 0x69, 0x21];
+    /// let sr = Sr { popped: &popped, received: &received };
+    /// let dr = Dr { popped: &popped, received: &received };
+    /// let rx = Fifo::new(&sr, &dr);
+    ///
+    /// let mut drain = rx.drain_while(|sr: u32| sr & RXNE != 0);
+    /// assert_eq!(drain.by_ref().collect::<Vec<_>>(), [0x68, 0x69, 0x21]);
+    /// assert_eq!(popped.get(), 3);
+    ///
+    /// // stopped at the empty status: the data register isn't read again
+    /// assert_eq!(drain.next(), None);
+    /// assert_eq!(popped.get(), 3);
+    ///
+    /// // or at the first status `available` rejects
+    /// popped.set(0);
+    /// let mut reads = 0;
+    /// let first_two: Vec<u32> = rx.drain_while(|_: u32| { reads += 1; reads <= 2 }).collect();
+    /// assert_eq!(first_two, [0x68, 0x69]);
+    /// assert_eq!(popped.get(), 2);
+    /// ```
+    pub fn drain_while<U, T, F>(&self, available: F) -> DrainWhile<'a, S, D, U, T, F>
+        where S: Read<U>, D: Read<T>, U: Copy, T: Copy, F: FnMut(U) -> bool
+    {
+        DrainWhile {
+            status: self.status,
+            data: self.data,
+            available,
+            done: false,
+            _marker: PhantomData,
+        }
+    }
+}
+
+/// Iterator returned by [`Fifo::drain_while`]
+pub struct DrainWhile<'a, S, D, U, T, F>
+    where S: 'a, D: 'a
+{
+    status: &'a S,
+    data: &'a D,
+    available: F,
+    done: bool,
+    _marker: PhantomData<fn() -> (U, T)>,
+}
+
+impl<'a, S, D, U, T, F> Iterator for DrainWhile<'a, S, D, U, T, F>
+    where S: Read<U>, D: Read<T>, U: Copy, T: Copy, F: FnMut(U) -> bool
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.done {
+            return None;
+        }
+        if (self.available)(self.status.read()) {
+            Some(self.data.read())
+        } else {
+            self.done = true;
+            None
+        }
+    }
+}
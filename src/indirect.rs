@@ -0,0 +1,103 @@
+//! Indirect register accesses
+//!
+//! With the `indirect` feature enabled every register access made through this
+//! crate is forwarded to a [`Vtable`] installed at runtime, when there's one.
+//! This lets the same firmware image run on hardware and under a hypervisor or
+//! an instruction set simulator that emulates the peripherals through
+//! callbacks, instead of trapping on each MMIO access.
+//!
+//! Until a vtable is installed, and after it's been removed, accesses go
+//! straight to memory.
+//!
+//! Reads produce values of the register's type out of the bytes the vtable
+//! returns, so installing a vtable is `unsafe`: its `read` callback must
+//! only return valid values. Any bytes are valid values of the integer
+//! types registers usually hold.
+//!
+//! ```
+//! use volatile_register::indirect::{self, Vtable};
+//!
+//! fn read(address: usize, buffer: &mut [u8]) {
+//!     // e.g. forward the access to the simulator
+//!     # let _ = address;
+//!     for byte in buffer {
+//!         *byte = 0;
+//!     }
+//! }
+//!
+//! fn write(address: usize, data: &[u8]) {
+//!     // e.g. forward the access to the simulator
+//!     # let _ = (address, data);
+//! }
+//!
+//! static VTABLE: Vtable = Vtable { read, write };
+//!
+//! // `read` returns zeroes, a valid value of any integer type
+//! unsafe { indirect::install(&VTABLE) }
+//! ```
+
+use core::mem;
+use core::ptr;
+use core::slice;
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use raw;
+
+/// Register access callbacks
+pub struct Vtable {
+    /// Reads `buffer.len()` bytes starting at `address` into `buffer`
+    ///
+    /// The bytes are in the target's native byte order. `buffer` is zeroed
+    /// beforehand.
+    pub read: fn(address: usize, buffer: &mut [u8]),
+    /// Writes `data` starting at `address`
+    ///
+    /// The bytes are in the target's native byte order.
+    pub write: fn(address: usize, data: &[u8]),
+}
+
+static VTABLE: AtomicPtr<Vtable> = AtomicPtr::new(ptr::null_mut());
+
+/// Forwards all subsequent register accesses to `vtable`
+///
+/// # Safety
+///
+/// The bytes the `read` callback leaves in its buffer must be a valid value
+/// of the type of the register being read, e.g. `0` or `1` for a `bool`
+pub unsafe fn install(vtable: &'static Vtable) {
+    VTABLE.store(vtable as *const Vtable as *mut Vtable, Ordering::Release);
+}
+
+/// Removes the installed vtable, if any; subsequent register accesses go
+/// straight to memory
+pub fn uninstall() {
+    VTABLE.store(ptr::null_mut(), Ordering::Release);
+}
+
+fn vtable() -> Option<&'static Vtable> {
+    unsafe { VTABLE.load(Ordering::Acquire).as_ref() }
+}
+
+#[inline(always)]
+pub(crate) unsafe fn read<T>(src: *const T) -> T
+    where T: Copy
+{
+    match vtable() {
+        Some(vtable) => raw::read_bytes(|buffer| (vtable.read)(src as usize, buffer)),
+        None => ptr::read_volatile(src),
+    }
+}
+
+#[inline(always)]
+pub(crate) unsafe fn write<T>(dst: *mut T, value: T)
+    where T: Copy
+{
+    match vtable() {
+        Some(vtable) => {
+            let data = slice::from_raw_parts(&value as *const T as *const u8,
+                                             mem::size_of::<T>());
+            (vtable.write)(dst as usize, data);
+        }
+        None => ptr::write_volatile(dst, value),
+    }
+}
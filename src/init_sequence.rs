@@ -0,0 +1,164 @@
+use poll::{Backoff, Timeout};
+use {Read, Write};
+
+/// Bring-up sequence of register writes and waits
+///
+/// Starting a PLL, a PHY or an external memory controller is a ladder of
+/// "write this, then wait for that bit, then write this". An
+/// `InitSequence` holds up to `N` such steps and runs them in order with a
+/// single call. Every wait has its own bound on the number of polls, and
+/// the first one that runs out stops the sequence and reports its index
+/// along with the last value read.
+///
+/// ```
+/// use volatile_register::poll::Spin;
+/// use volatile_register::{InitSequence, StepFailed, RO, RW};
+///
+/// #[repr(C)]
+/// pub struct Rcc {
+///     pub cr: RW<u32>,
+///     pub sr: RO<u32>,
+/// }
+///
+/// const PLLON: u32 = 1 << 24;
+/// const PLLRDY: u32 = 1 << 25;
+///
+/// let mut memory = [0u32; 2];
+/// let rcc = unsafe { &*(memory.as_mut_ptr() as *const Rcc) };
+///
+/// let pll = InitSequence::<u32, 2>::new()
+///     .write(&rcc.cr, PLLON)
+///     .wait(&rcc.sr, |sr| sr & PLLRDY != 0, 1_000);
+///
+/// // nothing sets PLLRDY in RAM
+/// assert_eq!(unsafe { pll.run(&mut Spin) }, Err(StepFailed { step: 1, last: Some(0) }));
+/// assert_eq!(rcc.cr.read(), PLLON);
+/// ```
+pub struct InitSequence<'a, T, const N: usize>
+    where T: Copy
+{
+    steps: [Option<Step<'a, T>>; N],
+    len: usize,
+}
+
+enum Step<'a, T> {
+    Write(&'a dyn Write<T>, T),
+    Wait(&'a dyn Read<T>, fn(T) -> bool, usize),
+}
+
+impl<'a, T> Clone for Step<'a, T>
+    where T: Copy
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for Step<'a, T> where T: Copy {}
+
+/// Polls a register through the `wait_until` of the `Read` trait, which
+/// isn't available on trait objects
+struct Dyn<'a, T>(&'a dyn Read<T>);
+
+impl<'a, T> Read<T> for Dyn<'a, T>
+    where T: Copy
+{
+    #[inline(always)]
+    fn read(&self) -> T {
+        self.0.read()
+    }
+}
+
+impl<'a, T, const N: usize> InitSequence<'a, T, N>
+    where T: Copy
+{
+    /// Creates an empty sequence
+    pub const fn new() -> Self {
+        InitSequence { steps: [None; N], len: 0 }
+    }
+
+    /// Appends a write of `value` into `register`
+    ///
+    /// # Panics
+    ///
+    /// If the sequence already holds `N` steps
+    pub fn write<R>(self, register: &'a R, value: T) -> Self
+        where R: Write<T>
+    {
+        self.push(Step::Write(register, value))
+    }
+
+    /// Appends a wait for `register` to satisfy `predicate`, reading it at
+    /// most `max_attempts` times, like [`Read::wait_until`](trait.Read.html#method.wait_until)
+    ///
+    /// # Panics
+    ///
+    /// If the sequence already holds `N` steps
+    pub fn wait<R>(self, register: &'a R, predicate: fn(T) -> bool, max_attempts: usize) -> Self
+        where R: Read<T>
+    {
+        self.push(Step::Wait(register, predicate, max_attempts))
+    }
+
+    fn push(mut self, step: Step<'a, T>) -> Self {
+        assert!(self.len < N, "InitSequence is full");
+        self.steps[self.len] = Some(step);
+        self.len += 1;
+        self
+    }
+
+    /// Returns the number of steps
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the sequence has no step
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Runs the steps in order, calling `backoff` between two polls of a
+    /// wait step
+    ///
+    /// Stops at the first wait step that times out. The sequence is left
+    /// untouched and can be run again.
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    pub unsafe fn run<B>(&self, backoff: &mut B) -> Result<(), StepFailed<T>>
+        where B: Backoff
+    {
+        for (step, &kind) in self.steps[..self.len].iter().flatten().enumerate() {
+            match kind {
+                Step::Write(register, value) => register.write(value),
+                Step::Wait(register, predicate, max_attempts) => {
+                    if let Err(Timeout { last }) =
+                        Dyn(register).wait_until(predicate, max_attempts, backoff)
+                    {
+                        return Err(StepFailed { step, last });
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T, const N: usize> Default for InitSequence<'a, T, N>
+    where T: Copy
+{
+    fn default() -> Self {
+        InitSequence::new()
+    }
+}
+
+/// Error returned by [`InitSequence::run`] when a wait step times out
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StepFailed<T> {
+    /// Index of the step, starting at 0
+    pub step: usize,
+    /// The last value read from the register, or `None` if the step
+    /// allowed no read
+    pub last: Option<T>,
+}
@@ -0,0 +1,89 @@
+use core::ops::{BitAnd, BitOr, Not, Shl, Shr};
+
+/// Unsigned integer register values
+///
+/// This trait is sealed: it's implemented for `u8`, `u16`, `u32`, `u64` and
+/// `usize`.
+pub trait Int: Copy
+    + Eq
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + Not<Output = Self>
+    + Shl<u32, Output = Self>
+    + Shr<u32, Output = Self>
+    + private::Sealed
+{
+    /// `0`
+    const ZERO: Self;
+    /// Size of the integer in bits
+    const BITS: u32;
+}
+
+macro_rules! int {
+    ($($ty:ty,)+) => {
+        $(
+            impl private::Sealed for $ty {
+                fn write_le(self, bytes: &mut [u8]) {
+                    bytes.copy_from_slice(&self.to_le_bytes())
+                }
+
+                fn rev_bits(self) -> $ty {
+                    self.reverse_bits()
+                }
+
+                fn rev_bytes(self) -> $ty {
+                    self.swap_bytes()
+                }
+
+                fn to_u64(self) -> u64 {
+                    self as u64
+                }
+
+                fn from_u64(value: u64) -> $ty {
+                    value as $ty
+                }
+
+                fn read_le(bytes: &[u8]) -> $ty {
+                    let mut le = [0; core::mem::size_of::<$ty>()];
+                    le.copy_from_slice(bytes);
+                    <$ty>::from_le_bytes(le)
+                }
+            }
+
+            impl Int for $ty {
+                const ZERO: $ty = 0;
+                const BITS: u32 = <$ty>::BITS;
+            }
+        )+
+    }
+}
+
+int! {
+    u8,
+    u16,
+    u32,
+    u64,
+    usize,
+}
+
+pub(crate) mod private {
+    pub trait Sealed: Sized {
+        /// Writes `self` into `bytes`, least significant byte first
+        fn write_le(self, bytes: &mut [u8]);
+
+        /// Reads a value from `bytes`, least significant byte first
+        fn read_le(bytes: &[u8]) -> Self;
+
+        /// Reverses the order of the bits
+        fn rev_bits(self) -> Self;
+
+        /// Reverses the order of the bytes
+        fn rev_bytes(self) -> Self;
+
+        /// Widens `self`
+        fn to_u64(self) -> u64;
+
+        /// Truncates `value`
+        fn from_u64(value: u64) -> Self;
+    }
+}
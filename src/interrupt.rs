@@ -0,0 +1,214 @@
+//! Critical sections
+//!
+//! Updates that span several registers, like a timer's prescaler, reload
+//! value and enable bit, can be observed half done by an interrupt handler
+//! that runs between two of the writes. `free` runs a closure with
+//! interrupts disabled on the current core, so every access performed in it
+//! happens as one unit with respect to interrupts.
+//!
+//! ``` no_run
+//! use volatile_register::RW;
+//! # #[cfg(feature = "std")]
+//! use volatile_register::interrupt;
+//!
+//! #[repr(C)]
+//! pub struct Tim {
+//!     pub cr: RW<u32>,
+//!     pub psc: RW<u32>,
+//!     pub arr: RW<u32>,
+//! }
+//!
+//! # #[cfg(feature = "std")]
+//! # fn main() {
+//! let tim = unsafe { &*(0x4000_0000 as *const Tim) };
+//! interrupt::free(|_| unsafe {
+//!     tim.cr.modify(|cr| cr & !1);
+//!     tim.psc.write(71);
+//!     tim.arr.write(999);
+//!     tim.cr.modify(|cr| cr | 1);
+//! });
+//! # }
+//! # #[cfg(not(feature = "std"))]
+//! # fn main() {}
+//! ```
+//!
+//! `free` is available on:
+//!
+//! - ARM M-profile, RISC-V (machine mode) and Xtensa bare-metal targets,
+//!   where it masks interrupts,
+//! - Linux, Android, macOS, iOS, Windows, the BSDs, illumos and Solaris, with
+//!   the `std` feature. Applications have no interrupts there, so `free`
+//!   takes a process-wide lock instead: the critical sections of different
+//!   threads exclude each other.
+//!
+//! It's deliberately missing everywhere else, RTOS targets like ESP-IDF
+//! included, rather than handing out a [`CriticalSection`] that masks
+//! nothing: use the critical section primitive of the platform there.
+//!
+//! Interrupts are only disabled on the current core: on multi-core systems,
+//! registers shared between cores need a lock as well.
+//!
+//! On Xtensa cores with the `S32C1I` conditional store, like the ESP32,
+//! `RW<u32>::modify_atomic` updates a register shared with interrupt
+//! handlers, or with the other core, without masking interrupts.
+
+use core::marker::PhantomData;
+
+/// Proof that interrupts are disabled on the current core
+///
+/// Only handed out by `free`, for the duration of the closure.
+pub struct CriticalSection<'cs> {
+    _marker: PhantomData<&'cs ()>,
+}
+
+cfg_free! {
+    /// Runs `f` with interrupts disabled, then restores the previous
+    /// interrupt state
+    ///
+    /// Calls can be nested: interrupts are only re-enabled when the outermost
+    /// critical section ends, even if `f` unwinds.
+    #[inline(always)]
+    pub fn free<F, R>(f: F) -> R
+        where F: FnOnce(&CriticalSection) -> R
+    {
+        use core::sync::atomic::{compiler_fence, Ordering};
+
+        let _restore = Restore(acquire());
+        compiler_fence(Ordering::SeqCst);
+        let r = f(&CriticalSection { _marker: PhantomData });
+        compiler_fence(Ordering::SeqCst);
+        r
+    }
+}
+
+cfg_free! {
+    /// Restores the interrupt state saved by `acquire` when dropped
+    struct Restore(State);
+}
+
+cfg_free! {
+    impl Drop for Restore {
+        #[inline(always)]
+        fn drop(&mut self) {
+            unsafe { release(self.0) }
+        }
+    }
+}
+
+/// Whether interrupts were enabled
+#[cfg(all(target_os = "none",
+          any(all(target_arch = "arm", target_feature = "mclass"),
+              target_arch = "riscv32",
+              target_arch = "riscv64")))]
+type State = bool;
+
+/// The processor state
+#[cfg(all(target_os = "none", target_arch = "xtensa"))]
+type State = u32;
+
+cfg_free! {
+    /// Whether this is the outermost critical section of the thread
+    #[cfg(not(target_os = "none"))]
+    type State = bool;
+}
+
+/// Disables interrupts, returning whether they were enabled
+#[cfg(all(target_os = "none", target_arch = "arm", target_feature = "mclass"))]
+#[inline(always)]
+fn acquire() -> bool {
+    let primask: u32;
+    unsafe {
+        core::arch::asm!("mrs {}, PRIMASK", out(reg) primask,
+                         options(nomem, nostack, preserves_flags));
+        core::arch::asm!("cpsid i", options(nostack, preserves_flags));
+    }
+    primask & 1 == 0
+}
+
+/// Re-enables interrupts if `enabled`
+#[cfg(all(target_os = "none", target_arch = "arm", target_feature = "mclass"))]
+#[inline(always)]
+unsafe fn release(enabled: bool) {
+    if enabled {
+        core::arch::asm!("cpsie i", options(nostack, preserves_flags));
+    }
+}
+
+/// Disables interrupts, returning whether they were enabled
+#[cfg(all(target_os = "none", any(target_arch = "riscv32", target_arch = "riscv64")))]
+#[inline(always)]
+fn acquire() -> bool {
+    let mstatus: usize;
+    // clears MIE, mstatus bit 3
+    unsafe {
+        core::arch::asm!("csrrci {}, mstatus, 8", out(reg) mstatus, options(nostack));
+    }
+    mstatus & 8 != 0
+}
+
+/// Re-enables interrupts if `enabled`
+#[cfg(all(target_os = "none", any(target_arch = "riscv32", target_arch = "riscv64")))]
+#[inline(always)]
+unsafe fn release(enabled: bool) {
+    if enabled {
+        core::arch::asm!("csrsi mstatus, 8", options(nostack));
+    }
+}
+
+/// Raises the interrupt level to mask every maskable interrupt, returning
+/// the previous processor state
+#[cfg(all(target_os = "none", target_arch = "xtensa"))]
+#[inline(always)]
+fn acquire() -> u32 {
+    let ps: u32;
+    unsafe { core::arch::asm!("rsil {}, 15", out(reg) ps, options(nostack)) }
+    ps
+}
+
+/// Restores the processor state returned by `acquire`
+#[cfg(all(target_os = "none", target_arch = "xtensa"))]
+#[inline(always)]
+unsafe fn release(ps: u32) {
+    core::arch::asm!("wsr.ps {}", "rsync", in(reg) ps, options(nostack));
+}
+
+cfg_free! {
+    #[cfg(not(target_os = "none"))]
+    mod hosted {
+        use core::cell::RefCell;
+        use std::sync::{Mutex, MutexGuard, PoisonError};
+
+        static LOCK: Mutex<()> = Mutex::new(());
+
+        std::thread_local! {
+            static GUARD: RefCell<Option<MutexGuard<'static, ()>>> = const { RefCell::new(None) };
+        }
+
+        /// Takes the process-wide lock, unless this thread already holds it;
+        /// returns `true` if it took it
+        #[inline]
+        pub fn acquire() -> bool {
+            GUARD.with(|guard| {
+                let mut guard = guard.borrow_mut();
+                if guard.is_some() {
+                    return false;
+                }
+                *guard = Some(LOCK.lock().unwrap_or_else(PoisonError::into_inner));
+                true
+            })
+        }
+
+        /// Releases the process-wide lock if `outermost`
+        #[inline]
+        pub unsafe fn release(outermost: bool) {
+            if outermost {
+                GUARD.with(|guard| guard.borrow_mut().take());
+            }
+        }
+    }
+}
+
+cfg_free! {
+    #[cfg(not(target_os = "none"))]
+    use self::hosted::{acquire, release};
+}
@@ -0,0 +1,118 @@
+use {Int, Read, Write};
+
+/// Interrupt enables split across a set register, a clear register and a
+/// readable mask register
+///
+/// Writing ones into the set register enables the corresponding
+/// interrupts, writing ones into the clear register disables them, and the
+/// mask register reads back the enabled interrupts. Neither write is a
+/// read-modify-write, so enabling an interrupt never races with an
+/// interrupt handler that disables another.
+///
+/// ``` no_run
+/// use volatile_register::{IrqEnable, RO, WO};
+///
+/// #[repr(C)]
+/// pub struct Gpio {
+///     pub intenset: WO<u32>,
+///     pub intenclr: WO<u32>,
+///     pub inten: RO<u32>,
+/// }
+///
+/// let gpio = unsafe { &*(0x5000_0300 as *const Gpio) };
+/// let enable = IrqEnable::new(&gpio.intenset, &gpio.intenclr, &gpio.inten);
+///
+/// unsafe { enable.enable(1 << 3) }
+/// if enable.is_enabled(1 << 3) {
+///     unsafe { enable.disable(1 << 3) }
+/// }
+/// ```
+///
+/// With the registers emulated over a mask in RAM:
+///
+/// ```
+/// use volatile_register::{IrqEnable, Write, RW};
+///
+/// // Writing ones sets (clears) those bits of the mask
+/// pub struct IntenSet<'a>(&'a RW<u32>);
+/// pub struct IntenClr<'a>(&'a RW<u32>);
+///
+/// impl<'a> Write<u32> for IntenSet<'a> {
+///     unsafe fn write(&self, bits: u32) {
+///         self.0.modify(|inten| inten | bits)
+///     }
+/// }
+///
+/// impl<'a> Write<u32> for IntenClr<'a> {
+///     unsafe fn write(&self, bits: u32) {
+///         self.0.modify(|inten| inten & !bits)
+///     }
+/// }
+///
+/// let mut memory = 0b1000_0001_u32;
+/// let inten = unsafe { &*(&mut memory as *mut u32 as *const RW<u32>) };
+/// let (intenset, intenclr) = (IntenSet(inten), IntenClr(inten));
+/// let enable = IrqEnable::new(&intenset, &intenclr, inten);
+///
+/// unsafe { enable.enable(1 << 3) }
+/// assert_eq!(enable.enabled::<u32>(), 0b1000_1001);
+/// assert!(enable.is_enabled(1 << 3 | 1 << 7));
+///
+/// unsafe { enable.disable(1 << 0) }
+/// assert!(!enable.is_enabled(1 << 0));
+/// assert_eq!(memory, 0b1000_1000);
+/// ```
+pub struct IrqEnable<'a, S, C, M>
+    where S: 'a, C: 'a, M: 'a
+{
+    set: &'a S,
+    clear: &'a C,
+    mask: &'a M,
+}
+
+impl<'a, S, C, M> IrqEnable<'a, S, C, M> {
+    /// Groups the `set`, `clear` and `mask` registers
+    pub fn new(set: &'a S, clear: &'a C, mask: &'a M) -> Self {
+        IrqEnable { set, clear, mask }
+    }
+
+    /// Enables the interrupts set in `mask`
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    pub unsafe fn enable<T>(&self, mask: T)
+        where S: Write<T>, T: Copy
+    {
+        self.set.write(mask)
+    }
+
+    /// Disables the interrupts set in `mask`
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    pub unsafe fn disable<T>(&self, mask: T)
+        where C: Write<T>, T: Copy
+    {
+        self.clear.write(mask)
+    }
+
+    /// Reads the enabled interrupts
+    #[inline(always)]
+    pub fn enabled<T>(&self) -> T
+        where M: Read<T>, T: Copy
+    {
+        self.mask.read()
+    }
+
+    /// Returns `true` if all the interrupts set in `mask` are enabled
+    #[inline(always)]
+    pub fn is_enabled<T>(&self, mask: T) -> bool
+        where M: Read<T>, T: Int
+    {
+        self.enabled() & mask == mask
+    }
+}
@@ -0,0 +1,99 @@
+use {Read, Write};
+
+/// Interrupt flags: a status register paired with its write-one-to-clear
+/// register
+///
+/// `take` reads the pending flags and clears exactly the flags it read, so an
+/// interrupt that becomes pending between the read and the clear is not lost.
+///
+/// Peripherals that clear flags by writing ones into the status register
+/// itself can pass the same register as `status` and `clear`.
+///
+/// ``` no_run
+/// use volatile_register::{IrqFlags, RO, WO};
+///
+/// #[repr(C)]
+/// pub struct Uart {
+///     pub isr: RO<u32>,
+///     pub icr: WO<u32>,
+/// }
+///
+/// let uart = unsafe { &*(0x4000_4400 as *const Uart) };
+/// let flags = IrqFlags::new(&uart.isr, &uart.icr);
+///
+/// // in the interrupt handler
+/// let pending = unsafe { flags.take() };
+/// if pending & 1 != 0 {
+///     // handle RX
+/// }
+/// ```
+///
+/// With the registers in RAM, `take` writes back exactly the flags it
+/// returns:
+///
+/// ```
+/// use volatile_register::{IrqFlags, RO, WO};
+///
+/// #[repr(C)]
+/// pub struct Uart {
+///     pub isr: RO<u32>,
+///     pub icr: WO<u32>,
+/// }
+///
+/// let mut memory = [0b1000_0101_u32, 0];
+/// let uart = unsafe { &*(memory.as_mut_ptr() as *const Uart) };
+/// let flags = IrqFlags::new(&uart.isr, &uart.icr);
+///
+/// assert_eq!(unsafe { flags.take() }, 0b1000_0101);
+/// assert_eq!(memory, [0b1000_0101, 0b1000_0101]);
+/// ```
+pub struct IrqFlags<'a, S, C>
+    where S: 'a, C: 'a
+{
+    status: &'a S,
+    clear: &'a C,
+}
+
+impl<'a, S, C> IrqFlags<'a, S, C> {
+    /// Pairs the `status` register with its `clear` register
+    pub fn new(status: &'a S, clear: &'a C) -> Self {
+        IrqFlags { status, clear }
+    }
+
+    /// Reads the pending flags
+    #[inline(always)]
+    pub fn pending<T>(&self) -> T
+        where S: Read<T>, T: Copy
+    {
+        self.status.read()
+    }
+
+    /// Clears the flags set in `mask`
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    pub unsafe fn clear<T>(&self, mask: T)
+        where C: Write<T>, T: Copy
+    {
+        self.clear.write(mask)
+    }
+
+    /// Reads the pending flags and clears them
+    ///
+    /// Only the flags that were read are cleared; flags that become pending
+    /// afterwards are kept.
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    pub unsafe fn take<T>(&self) -> T
+        where S: Read<T>, C: Write<T>, T: Copy
+    {
+        let pending = self.status.read();
+        self.clear.write(pending);
+        pending
+    }
+}
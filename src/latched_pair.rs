@@ -0,0 +1,151 @@
+use {Read, Write};
+
+/// Coherent read of a value split across two registers
+///
+/// Peripherals that expose a wide value, like a 32-bit timer counter, through
+/// two narrower registers usually freeze both halves when a latch (capture)
+/// bit is written. `read` writes the latch command, then reads the low half
+/// and then the high half, and joins them.
+///
+/// ``` no_run
+/// use volatile_register::{LatchedPair, RO, WO};
+///
+/// #[repr(C)]
+/// pub struct Timer {
+///     pub ctrl: WO<u16>,
+///     pub cnt_lo: RO<u16>,
+///     pub cnt_hi: RO<u16>,
+/// }
+///
+/// const CAPTURE: u16 = 1 << 2;
+///
+/// let timer = unsafe { &*(0x4000_0c00 as *const Timer) };
+/// let counter = LatchedPair::new(&timer.ctrl, CAPTURE, &timer.cnt_lo, &timer.cnt_hi);
+/// let ticks: u32 = unsafe { counter.read() };
+/// ```
+///
+/// Recording the accesses through an `indirect` vtable, with the `indirect`
+/// feature:
+///
+/// ```
+/// # #[cfg(feature = "indirect")]
+/// # fn main() {
+/// use std::ptr;
+/// use std::sync::Mutex;
+///
+/// use volatile_register::indirect::{self, Vtable};
+/// use volatile_register::{LatchedPair, RO, WO};
+///
+/// #[repr(C)]
+/// pub struct Timer {
+///     pub ctrl: WO<u16>,
+///     pub cnt_lo: RO<u16>,
+///     pub cnt_hi: RO<u16>,
+/// }
+///
+/// // (address, write)
+/// static ACCESSES: Mutex<Vec<(usize, bool)>> = Mutex::new(Vec::new());
+///
+/// fn read(address: usize, buffer: &mut [u8]) {
+///     ACCESSES.lock().unwrap().push((address, false));
+///     unsafe { ptr::copy_nonoverlapping(address as *const u8, buffer.as_mut_ptr(), buffer.len()) }
+/// }
+///
+/// fn write(address: usize, data: &[u8]) {
+///     ACCESSES.lock().unwrap().push((address, true));
+///     unsafe { ptr::copy_nonoverlapping(data.as_ptr(), address as *mut u8, data.len()) }
+/// }
+///
+/// static VTABLE: Vtable = Vtable { read, write };
+///
+/// const CAPTURE: u16 = 1 << 2;
+///
+/// let mut memory = [0u16, 0x5678, 0x1234];
+/// let base = memory.as_mut_ptr() as usize;
+/// let timer = unsafe { &*(base as *const Timer) };
+/// let counter = LatchedPair::new(&timer.ctrl, CAPTURE, &timer.cnt_lo, &timer.cnt_hi);
+///
+/// // reads return the bytes of the RAM
+/// unsafe { indirect::install(&VTABLE) }
+/// let ticks: u32 = unsafe { counter.read() };
+/// indirect::uninstall();
+///
+/// assert_eq!(ticks, 0x1234_5678);
+/// // latch, low half, high half
+/// assert_eq!(*ACCESSES.lock().unwrap(), [(base, true), (base + 2, false), (base + 4, false)]);
+/// # }
+/// # #[cfg(not(feature = "indirect"))]
+/// # fn main() {}
+/// ```
+pub struct LatchedPair<'a, L, C, R>
+    where L: 'a, C: Copy, R: 'a
+{
+    latch: &'a L,
+    command: C,
+    low: &'a R,
+    high: &'a R,
+}
+
+impl<'a, L, C, R> LatchedPair<'a, L, C, R>
+    where L: Write<C>, C: Copy
+{
+    /// Creates the pair; `command` is written into `latch` to freeze the
+    /// `low` and `high` halves
+    pub fn new(latch: &'a L, command: C, low: &'a R, high: &'a R) -> Self {
+        LatchedPair { latch, command, low, high }
+    }
+
+    /// Latches the value, reads the low half, then the high half, and
+    /// returns the combined value
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writing the latch command is side effectful
+    #[inline(always)]
+    pub unsafe fn read<W>(&self) -> W
+        where W: Halves, R: Read<W::Half>
+    {
+        self.latch.write(self.command);
+        let low = self.low.read();
+        let high = self.high.read();
+        W::join(high, low)
+    }
+}
+
+/// Integers made of two halves
+pub trait Halves: Copy {
+    /// Integer half as wide as `Self`
+    type Half: Copy;
+
+    /// Joins the `high` and `low` halves
+    fn join(high: Self::Half, low: Self::Half) -> Self;
+
+    /// Splits `self` into its `(high, low)` halves
+    fn split(self) -> (Self::Half, Self::Half);
+}
+
+macro_rules! halves {
+    ($($wide:ty => $half:ty,)+) => {
+        $(
+            impl Halves for $wide {
+                type Half = $half;
+
+                #[inline(always)]
+                fn join(high: $half, low: $half) -> $wide {
+                    (<$wide>::from(high) << <$half>::BITS) | <$wide>::from(low)
+                }
+
+                #[inline(always)]
+                fn split(self) -> ($half, $half) {
+                    ((self >> <$half>::BITS) as $half, self as $half)
+                }
+            }
+        )+
+    }
+}
+
+halves! {
+    u16 => u8,
+    u32 => u16,
+    u64 => u32,
+}
@@ -24,15 +24,198 @@
 //! // Unsafe because the compiler can't verify the address is correct
 //! unsafe { (*nvic).iser[0].write(1) }
 //! ```
+//!
+//! # Emulation
+//!
+//! With the `emulation` feature enabled the register types can be created in
+//! RAM, through `new` or `Default` (zero for the integer types). Register
+//! blocks only made of registers and integer arrays can then simply
+//! `#[derive(Default)]` to get a zeroed fake peripheral for tests and
+//! emulators.
+//! Blocks declared with
+//! [`register_block!`](macro.register_block.html) get a `zeroed`
+//! constructor to the same effect.
 
 #![deny(missing_docs)]
 #![no_std]
+#![cfg_attr(all(feature = "nightly",
+                not(any(feature = "indirect", feature = "stats", feature = "timing", feature = "wasm"))),
+            feature(core_intrinsics),
+            allow(internal_features))]
+#![cfg_attr(target_arch = "xtensa", feature(asm_experimental_arch))]
 
+#[cfg(feature = "std")]
+extern crate std;
 extern crate vcell;
+#[cfg(feature = "device-driver")]
+extern crate device_driver;
+
+/// Keeps the item only on the targets where
+/// [`interrupt::free`](interrupt/fn.free.html) exists: bare-metal targets
+/// whose interrupts it can mask, and hosted operating systems, with the
+/// `std` feature, where it takes a process-wide lock
+///
+/// `cfg_free! { ! .. }` keeps the item only on the other targets.
+macro_rules! cfg_free {
+    (@ $op:ident $($item:tt)*) => {
+        #[cfg($op(any(all(feature = "std",
+                          any(target_os = "linux",
+                              target_os = "android",
+                              target_os = "macos",
+                              target_os = "ios",
+                              target_os = "windows",
+                              target_os = "freebsd",
+                              target_os = "netbsd",
+                              target_os = "openbsd",
+                              target_os = "dragonfly",
+                              target_os = "illumos",
+                              target_os = "solaris")),
+                      all(target_os = "none",
+                          any(all(target_arch = "arm", target_feature = "mclass"),
+                              target_arch = "riscv32",
+                              target_arch = "riscv64",
+                              target_arch = "xtensa")))))]
+        $($item)*
+    };
+    (! $($item:tt)*) => { cfg_free! { @ not $($item)* } };
+    ($($item:tt)*) => { cfg_free! { @ all $($item)* } };
+}
+
+/// Keeps the item only on the targets where a
+/// [`TakeOnce`](struct.TakeOnce.html) flag can be taken: those with 8-bit
+/// atomic swaps, and those where `interrupt::free` exists
+macro_rules! cfg_take_once {
+    ($($item:tt)*) => {
+        #[cfg(target_has_atomic = "8")]
+        $($item)*
+        cfg_free! {
+            #[cfg(not(target_has_atomic = "8"))]
+            $($item)*
+        }
+    };
+}
+
+pub mod access;
+pub mod aggregate;
+pub mod backend;
+mod banked;
+pub mod barrier;
+mod cached_ro;
+mod command;
+pub mod compiler_fenced;
+#[cfg(all(target_arch = "xtensa", target_has_atomic = "32"))]
+mod conditional_store;
+pub mod describe;
+mod doorbell;
+pub mod dump;
+mod field;
+mod fifo;
+#[cfg(feature = "indirect")]
+pub mod indirect;
+mod init_sequence;
+mod int;
+pub mod interrupt;
+mod irq_enable;
+mod irq_flags;
+mod latched_pair;
+mod mailbox;
+mod masked_wo;
+mod modify_guard;
+#[cfg(all(feature = "std", target_os = "linux", target_pointer_width = "64"))]
+pub mod pci;
+mod overlay;
+mod paced;
+cfg_take_once! { mod peripheral; }
+pub mod poll;
+mod powered_region;
+mod pure;
+mod raw;
+mod read_together;
+mod register_block;
+#[cfg(feature = "device-driver")]
+pub mod register_interface;
+cfg_take_once! { mod registry; }
+mod rev;
+#[cfg(feature = "sim")]
+pub mod sim;
+mod snapshot;
+mod split_access;
+cfg_take_once! {
+    #[cfg(feature = "stats")]
+    pub mod stats;
+}
+#[cfg(feature = "timing")]
+pub mod timing;
+mod torn;
+mod variants;
+mod volatile_buf;
+mod volatile_ref;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+mod write_batch;
+
+use core::cell::UnsafeCell;
+use core::convert::Infallible;
+use core::mem::MaybeUninit;
 
 use vcell::VolatileCell;
 
+use poll::{Backoff, Timeout};
+
+pub use banked::Banked;
+pub use cached_ro::CachedRO;
+pub use command::Command;
+pub use doorbell::{write_then_notify, Doorbell};
+pub use field::{Field, FieldOverflow, FieldValue};
+pub use fifo::{DrainWhile, Fifo};
+pub use init_sequence::{InitSequence, StepFailed};
+pub use int::Int;
+pub use irq_enable::IrqEnable;
+pub use irq_flags::IrqFlags;
+pub use latched_pair::{Halves, LatchedPair};
+pub use mailbox::{Mailbox, WouldBlock};
+pub use masked_wo::{HighMask, MaskEncoding, MaskedWO, SetReset};
+pub use modify_guard::ModifyGuard;
+pub use overlay::{Overlay, OverlayView};
+pub use paced::{Pace, Paced, SyncBusy};
+cfg_take_once! { pub use peripheral::{Peripheral, TakeOnce}; }
+pub use powered_region::PoweredRegion;
+pub use pure::{Pure, PureRead};
+pub use read_together::{read_together, read_together_consistent, ReadTogether};
+pub use register_block::{Corrupted, NoPureRegister, RegisterBlock, RegisterEntry, RegisterInfo,
+                         Registers, Resettable};
+pub use rev::{BitRev, ByteRev};
+pub use snapshot::{BufferTooSmall, ParseError};
+pub use split_access::SplitAccess;
+pub use torn::Torn;
+pub use variants::UnknownRevision;
+pub use volatile_buf::{copy_volatile, DmaHandle, VolatileBuf};
+pub use volatile_ref::VolatileRef;
+pub use write_batch::{BatchFull, WriteBatch};
+
+#[doc(hidden)]
+pub use core::mem::{offset_of as __offset_of, size_of as __size_of};
+
 /// Read-Only register
+///
+/// Registers live in device memory and are neither `Copy` nor `Clone`, so
+/// they can't be accidentally moved out of it:
+///
+/// ``` compile_fail
+/// use volatile_register::RO;
+///
+/// fn steal(register: &RO<u32>) -> RO<u32> {
+///     *register
+/// }
+/// ```
+///
+/// ``` compile_fail
+/// use volatile_register::RO;
+///
+/// fn steal(register: &RO<u32>) -> RO<u32> {
+///     register.clone()
+/// }
+/// ```
 #[repr(transparent)]
 pub struct RO<T>
     where T: Copy
@@ -40,17 +223,139 @@ pub struct RO<T>
     register: VolatileCell<T>,
 }
 
+#[cfg(feature = "emulation")]
 impl<T> RO<T>
     where T: Copy
 {
+    /// Creates a register, in RAM, holding `value`
+    pub const fn new(value: T) -> Self {
+        RO { register: VolatileCell::new(value) }
+    }
+}
+
+#[cfg(feature = "emulation")]
+impl<T> Default for RO<T>
+    where T: Copy + Default
+{
+    fn default() -> Self {
+        RO::new(T::default())
+    }
+}
+
+impl<T> RO<T>
+    where T: Copy
+{
+    /// Returns the address of the register
+    ///
+    /// Handy for error messages, like "timeout waiting on register
+    /// @0x4001_0810".
+    #[inline(always)]
+    pub fn addr(&self) -> usize {
+        self as *const RO<T> as usize
+    }
+
     /// Reads the value of the register
     #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
     pub fn read(&self) -> T {
-        self.register.get()
+        unsafe { raw::read(self.register.as_ptr()) }
+    }
+
+    /// Reads the value of the register into a snapshot
+    #[inline(always)]
+    pub fn snapshot(&self) -> ReadValue<T> {
+        ReadValue::new(self.read())
+    }
+
+    /// Reads the register until two consecutive reads return the same value
+    ///
+    /// Registers updated from another clock domain (RTC or timer counters,
+    /// for example) can be observed in the middle of an update; datasheets
+    /// usually ask for them to be re-read until the value settles.
+    ///
+    /// At most `max_attempts` reads are performed, like
+    /// [`Read::wait_until`](trait.Read.html#method.wait_until).
+    /// `Err(Unstable)` is returned if no two consecutive reads matched, so
+    /// at least 2 attempts are needed to succeed; with `max_attempts == 0`
+    /// the register is not read at all.
+    ///
+    /// ```
+    /// use volatile_register::{Unstable, RO};
+    ///
+    /// let mut memory = 42u32;
+    /// let cnt = unsafe { &*(&mut memory as *mut u32 as *const RO<u32>) };
+    ///
+    /// assert_eq!(cnt.read_stable(2), Ok(42));
+    /// assert_eq!(cnt.read_stable(1), Err(Unstable));
+    /// assert_eq!(cnt.read_stable(0), Err(Unstable));
+    /// ```
+    pub fn read_stable(&self, max_attempts: usize) -> Result<T, Unstable>
+        where T: PartialEq
+    {
+        if max_attempts == 0 {
+            return Err(Unstable);
+        }
+        let mut previous = self.read();
+        for _ in 1..max_attempts {
+            let current = self.read();
+            if current == previous {
+                return Ok(current);
+            }
+            previous = current;
+        }
+        Err(Unstable)
+    }
+}
+
+/// Error returned by [`RO::read_stable`] when the register never settled
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Unstable;
+
+/// Snapshot of the contents of a register
+///
+/// Unlike the register it was read from, a snapshot is plain data that lives
+/// in RAM: it can be copied, compared and kept around as long as needed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ReadValue<T>
+    where T: Copy
+{
+    value: T,
+}
+
+impl<T> ReadValue<T>
+    where T: Copy
+{
+    /// Creates a snapshot holding `value`
+    pub const fn new(value: T) -> Self {
+        ReadValue { value }
+    }
+
+    /// Returns the value held by the snapshot
+    pub fn get(&self) -> T {
+        self.value
     }
 }
 
 /// Read-Write register
+///
+/// Registers live in device memory and are neither `Copy` nor `Clone`, so
+/// they can't be accidentally moved out of it:
+///
+/// ``` compile_fail
+/// use volatile_register::RW;
+///
+/// fn steal(register: &RW<u32>) -> RW<u32> {
+///     *register
+/// }
+/// ```
+///
+/// ``` compile_fail
+/// use volatile_register::RW;
+///
+/// fn steal(register: &RW<u32>) -> RW<u32> {
+///     register.clone()
+/// }
+/// ```
 #[repr(transparent)]
 pub struct RW<T>
     where T: Copy
@@ -58,50 +363,545 @@ pub struct RW<T>
     register: VolatileCell<T>,
 }
 
+#[cfg(feature = "emulation")]
 impl<T> RW<T>
     where T: Copy
 {
+    /// Creates a register, in RAM, holding `value`
+    ///
+    /// ```
+    /// use volatile_register::{RO, RW};
+    ///
+    /// #[derive(Default)]
+    /// #[repr(C)]
+    /// pub struct Uart {
+    ///     pub dr: RW<u32>,
+    ///     pub sr: RO<u32>,
+    /// }
+    ///
+    /// let uart = Uart::default();
+    /// assert_eq!(uart.sr.read(), 0);
+    /// unsafe { uart.dr.write(0x55) }
+    /// assert_eq!(uart.dr.read(), 0x55);
+    /// ```
+    pub const fn new(value: T) -> Self {
+        RW { register: VolatileCell::new(value) }
+    }
+}
+
+#[cfg(feature = "emulation")]
+impl<T> Default for RW<T>
+    where T: Copy + Default
+{
+    fn default() -> Self {
+        RW::new(T::default())
+    }
+}
+
+impl<T> RW<T>
+    where T: Copy
+{
+    /// Returns the address of the register
+    #[inline(always)]
+    pub fn addr(&self) -> usize {
+        self as *const RW<T> as usize
+    }
+
+    /// Returns a read-only view of the register
+    ///
+    /// The view can be handed to code that monitors the register but must
+    /// not be able to change it.
+    ///
+    /// ```
+    /// use volatile_register::{RO, RW};
+    ///
+    /// fn log(register: &RO<u32>) -> u32 {
+    ///     register.read()
+    /// }
+    ///
+    /// let mut memory = 42u32;
+    /// let ctrl = unsafe { &*(&mut memory as *mut u32 as *const RW<u32>) };
+    /// assert_eq!(log(ctrl.as_ro()), 42);
+    /// ```
+    #[inline(always)]
+    pub fn as_ro(&self) -> &RO<T> {
+        // both are `repr(transparent)` over a `VolatileCell<T>`
+        unsafe { &*(self as *const RW<T> as *const RO<T>) }
+    }
+
+    /// Returns a write-only view of the register
+    #[inline(always)]
+    pub fn as_wo(&self) -> &WO<T> {
+        // `WO<T>` is `repr(transparent)` over an
+        // `UnsafeCell<MaybeUninit<T>>`, which has the layout of the
+        // `UnsafeCell<T>` of the `VolatileCell<T>`, and every `T` is a valid
+        // `MaybeUninit<T>`
+        unsafe { &*(self as *const RW<T> as *const WO<T>) }
+    }
+
     /// Performs a read-modify-write operation
     ///
-    /// NOTE: `unsafe` because writes to a register are side effectful
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
     #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
     pub unsafe fn modify<F>(&self, f: F)
         where F: FnOnce(T) -> T
     {
-        self.register.set(f(self.register.get()));
+        let register = self.register.as_ptr();
+        raw::write(register, f(raw::read(register)));
     }
 
     /// Reads the value of the register
     #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
     pub fn read(&self) -> T {
-        self.register.get()
+        unsafe { raw::read(self.register.as_ptr()) }
+    }
+
+    /// Reads the value of the register into a snapshot
+    #[inline(always)]
+    pub fn snapshot(&self) -> ReadValue<T> {
+        ReadValue::new(self.read())
     }
 
     /// Writes a `value` into the register
     ///
-    /// NOTE: `unsafe` because writes to a register are side effectful
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
     #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
     pub unsafe fn write(&self, value: T) {
-        self.register.set(value)
+        raw::write(self.register.as_ptr(), value)
+    }
+
+    /// Writes a `value` into the register, then reads `flush`
+    ///
+    /// On buses with posted writes, a write only reaches the device some time
+    /// after the store instruction retires; reading back a register of the
+    /// same device waits until it has. Use a register whose reads have no
+    /// side effects as `flush`.
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn write_flush<R, U>(&self, value: T, flush: &R)
+        where R: Read<U>, U: Copy
+    {
+        self.write(value);
+        flush.read();
     }
 }
 
 /// Write-Only register
+///
+/// Reading a write-only register usually returns an unspecified value, so a
+/// `WO<T>` never assumes that it holds an initialized `T`: it can be created
+/// over device memory whose contents are undefined.
+///
+/// Registers live in device memory and are neither `Copy` nor `Clone`, so
+/// they can't be accidentally moved out of it:
+///
+/// ``` compile_fail
+/// use volatile_register::WO;
+///
+/// fn steal(register: &WO<u32>) -> WO<u32> {
+///     *register
+/// }
+/// ```
+///
+/// ``` compile_fail
+/// use volatile_register::WO;
+///
+/// fn steal(register: &WO<u32>) -> WO<u32> {
+///     register.clone()
+/// }
+/// ```
 #[repr(transparent)]
 pub struct WO<T>
     where T: Copy
 {
-    register: VolatileCell<T>,
+    register: UnsafeCell<MaybeUninit<T>>,
+}
+
+#[cfg(feature = "emulation")]
+impl<T> WO<T>
+    where T: Copy
+{
+    /// Creates a register, in RAM, holding `value`
+    pub const fn new(value: T) -> Self {
+        WO { register: UnsafeCell::new(MaybeUninit::new(value)) }
+    }
+}
+
+#[cfg(feature = "emulation")]
+impl<T> Default for WO<T>
+    where T: Copy + Default
+{
+    fn default() -> Self {
+        WO::new(T::default())
+    }
 }
 
 impl<T> WO<T>
     where T: Copy
 {
+    /// Returns the address of the register
+    #[inline(always)]
+    pub fn addr(&self) -> usize {
+        self as *const WO<T> as usize
+    }
+
+    #[inline(always)]
+    pub(crate) fn as_ptr(&self) -> *mut T {
+        self.register.get() as *mut T
+    }
+
     /// Writes `value` into the register
     ///
-    /// NOTE: `unsafe` because writes to a register are side effectful
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
     #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
     pub unsafe fn write(&self, value: T) {
-        self.register.set(value)
+        raw::write(self.as_ptr(), value)
+    }
+
+    /// Writes a `value` into the register, then reads `flush`
+    ///
+    /// On buses with posted writes, a write only reaches the device some time
+    /// after the store instruction retires; reading back a register of the
+    /// same device waits until it has. Use a register whose reads have no
+    /// side effects as `flush`.
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn write_flush<R, U>(&self, value: T, flush: &R)
+        where R: Read<U>, U: Copy
+    {
+        self.write(value);
+        flush.read();
+    }
+}
+
+/// Registers that can be read
+pub trait Read<T>
+    where T: Copy
+{
+    /// Reads the value of the register
+    fn read(&self) -> T;
+
+    /// Polls the register until `predicate` returns `true` for the value read
+    ///
+    /// `backoff` is called between polls. At most `max_attempts` reads are
+    /// performed; on success the value that satisfied `predicate` is
+    /// returned. With `max_attempts == 0` the register is not read at all.
+    ///
+    /// ``` no_run
+    /// use volatile_register::poll::Spin;
+    /// use volatile_register::{Read, RO};
+    ///
+    /// let sr = unsafe { &*(0x4000_4400 as *const RO<u32>) };
+    /// // wait for TX empty
+    /// sr.wait_until(|sr| sr & (1 << 7) != 0, 10_000, &mut Spin).unwrap();
+    /// ```
+    ///
+    /// ```
+    /// use volatile_register::poll::{Spin, Timeout};
+    /// use volatile_register::{Read, RO};
+    ///
+    /// let mut memory = 1u32;
+    /// let sr = unsafe { &*(&mut memory as *mut u32 as *const RO<u32>) };
+    ///
+    /// assert_eq!(sr.wait_until(|sr| sr == 1, 1, &mut Spin), Ok(1));
+    /// assert_eq!(sr.wait_until(|sr| sr == 2, 1, &mut Spin), Err(Timeout { last: Some(1) }));
+    /// assert_eq!(sr.wait_until(|sr| sr == 1, 0, &mut Spin), Err(Timeout { last: None }));
+    /// ```
+    fn wait_until<F, B>(&self,
+                        mut predicate: F,
+                        max_attempts: usize,
+                        backoff: &mut B)
+                        -> Result<T, Timeout<T>>
+        where Self: Sized, F: FnMut(T) -> bool, B: Backoff
+    {
+        let mut last = None;
+        for attempt in 0..max_attempts {
+            if attempt != 0 {
+                backoff.backoff();
+            }
+            let value = self.read();
+            if predicate(value) {
+                return Ok(value);
+            }
+            last = Some(value);
+        }
+        Err(Timeout { last })
+    }
+
+    /// Polls the register until it holds a value other than `previous`
+    ///
+    /// Like [`wait_until`](#method.wait_until), `max_attempts` bounds the
+    /// number of reads.
+    ///
+    /// ```
+    /// use volatile_register::poll::{Spin, Timeout};
+    /// use volatile_register::{Read, RO};
+    ///
+    /// let mut memory = 42u32;
+    /// let cnt = unsafe { &*(&mut memory as *mut u32 as *const RO<u32>) };
+    ///
+    /// assert_eq!(cnt.wait_for_change(42, 3, &mut Spin), Err(Timeout { last: Some(42) }));
+    /// assert_eq!(cnt.wait_for_change(0, 3, &mut Spin), Ok(42));
+    /// ```
+    fn wait_for_change<B>(&self, previous: T, max_attempts: usize, backoff: &mut B)
+                          -> Result<T, Timeout<T>>
+        where Self: Sized, T: PartialEq, B: Backoff
+    {
+        self.wait_until(|value| value != previous, max_attempts, backoff)
+    }
+
+    /// Polls the register until the bits selected by `mask` go from all
+    /// clear to not all clear
+    ///
+    /// The transition has to be observed: if some of the bits are already
+    /// set at the first read, they have to be seen clear before the wait can
+    /// succeed. Like [`wait_until`](#method.wait_until), `max_attempts` bounds
+    /// the number of reads.
+    ///
+    /// ```
+    /// use std::cell::Cell;
+    ///
+    /// use volatile_register::poll::{Spin, Timeout};
+    /// use volatile_register::Read;
+    ///
+    /// /// Input data register replaying samples, then holding the last one
+    /// struct Idr {
+    ///     samples: &'static [u32],
+    ///     reads: Cell<usize>,
+    /// }
+    ///
+    /// impl Read<u32> for Idr {
+    ///     fn read(&self) -> u32 {
+    ///         let reads = self.reads.get();
+    ///         self.reads.set(reads + 1);
+    ///         self.samples[reads.min(self.samples.len() - 1)]
+    ///     }
+    /// }
+    ///
+    /// const PIN3: u32 = 1 << 3;
+    ///
+    /// // low, low, then high: the edge is seen on the third read
+    /// let idr = Idr { samples: &[0, 0, PIN3 | 1], reads: Cell::new(0) };
+    /// assert_eq!(idr.wait_for_rising(PIN3, 10, &mut Spin), Ok(PIN3 | 1));
+    /// assert_eq!(idr.reads.get(), 3);
+    ///
+    /// // already high at the first read: not an edge, the pin has to go low
+    /// // first
+    /// let idr = Idr { samples: &[PIN3, PIN3, 0, PIN3], reads: Cell::new(0) };
+    /// assert_eq!(idr.wait_for_rising(PIN3, 10, &mut Spin), Ok(PIN3));
+    /// assert_eq!(idr.reads.get(), 4);
+    ///
+    /// // high all along
+    /// let idr = Idr { samples: &[PIN3], reads: Cell::new(0) };
+    /// assert_eq!(idr.wait_for_rising(PIN3, 5, &mut Spin), Err(Timeout { last: Some(PIN3) }));
+    /// assert_eq!(idr.reads.get(), 5);
+    /// ```
+    fn wait_for_rising<B>(&self, mask: T, max_attempts: usize, backoff: &mut B)
+                          -> Result<T, Timeout<T>>
+        where Self: Sized, T: Int, B: Backoff
+    {
+        let mut armed = false;
+        self.wait_until(|value| {
+                            let set = value & mask != T::ZERO;
+                            let edge = armed && set;
+                            armed = !set;
+                            edge
+                        },
+                        max_attempts,
+                        backoff)
+    }
+
+    /// Polls the register until the bits selected by `mask` go from not all
+    /// clear to all clear
+    ///
+    /// The counterpart of [`wait_for_rising`](#method.wait_for_rising).
+    ///
+    /// ```
+    /// use std::cell::Cell;
+    ///
+    /// use volatile_register::poll::{Spin, Timeout};
+    /// use volatile_register::Read;
+    ///
+    /// /// Status register replaying samples, then holding the last one
+    /// struct Sr {
+    ///     samples: &'static [u32],
+    ///     reads: Cell<usize>,
+    /// }
+    ///
+    /// impl Read<u32> for Sr {
+    ///     fn read(&self) -> u32 {
+    ///         let reads = self.reads.get();
+    ///         self.reads.set(reads + 1);
+    ///         self.samples[reads.min(self.samples.len() - 1)]
+    ///     }
+    /// }
+    ///
+    /// const BUSY: u32 = 1 << 7;
+    ///
+    /// // already clear at the first read: not an edge
+    /// let sr = Sr { samples: &[0, BUSY | 1, BUSY, 1], reads: Cell::new(0) };
+    /// assert_eq!(sr.wait_for_falling(BUSY, 10, &mut Spin), Ok(1));
+    /// assert_eq!(sr.reads.get(), 4);
+    ///
+    /// // busy all along
+    /// let sr = Sr { samples: &[BUSY], reads: Cell::new(0) };
+    /// assert_eq!(sr.wait_for_falling(BUSY, 5, &mut Spin), Err(Timeout { last: Some(BUSY) }));
+    /// assert_eq!(sr.reads.get(), 5);
+    /// ```
+    fn wait_for_falling<B>(&self, mask: T, max_attempts: usize, backoff: &mut B)
+                           -> Result<T, Timeout<T>>
+        where Self: Sized, T: Int, B: Backoff
+    {
+        let mut armed = false;
+        self.wait_until(|value| {
+                            let clear = value & mask == T::ZERO;
+                            let edge = armed && clear;
+                            armed = !clear;
+                            edge
+                        },
+                        max_attempts,
+                        backoff)
+    }
+}
+
+/// Registers that can be written
+pub trait Write<T>
+    where T: Copy
+{
+    /// Writes a `value` into the register
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    unsafe fn write(&self, value: T);
+}
+
+impl<T> Read<T> for RO<T>
+    where T: Copy
+{
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    fn read(&self) -> T {
+        RO::read(self)
+    }
+}
+
+impl<T> Read<T> for RW<T>
+    where T: Copy
+{
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    fn read(&self) -> T {
+        RW::read(self)
+    }
+}
+
+impl<T> Write<T> for RW<T>
+    where T: Copy
+{
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    unsafe fn write(&self, value: T) {
+        RW::write(self, value)
+    }
+}
+
+impl<T> Write<T> for WO<T>
+    where T: Copy
+{
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    unsafe fn write(&self, value: T) {
+        WO::write(self, value)
+    }
+}
+
+/// Registers that can be read through an access path that can fail
+pub trait TryRead<T>
+    where T: Copy
+{
+    /// Error reported by failed reads
+    type Error;
+
+    /// Reads the value of the register
+    fn try_read(&self) -> Result<T, Self::Error>;
+}
+
+/// Registers that can be written through an access path that can fail
+pub trait TryWrite<T>
+    where T: Copy
+{
+    /// Error reported by failed writes
+    type Error;
+
+    /// Writes a `value` into the register
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    unsafe fn try_write(&self, value: T) -> Result<(), Self::Error>;
+}
+
+impl<T> TryRead<T> for RO<T>
+    where T: Copy
+{
+    type Error = Infallible;
+
+    #[inline(always)]
+    fn try_read(&self) -> Result<T, Infallible> {
+        Ok(RO::read(self))
+    }
+}
+
+impl<T> TryRead<T> for RW<T>
+    where T: Copy
+{
+    type Error = Infallible;
+
+    #[inline(always)]
+    fn try_read(&self) -> Result<T, Infallible> {
+        Ok(RW::read(self))
+    }
+}
+
+impl<T> TryWrite<T> for RW<T>
+    where T: Copy
+{
+    type Error = Infallible;
+
+    #[inline(always)]
+    unsafe fn try_write(&self, value: T) -> Result<(), Infallible> {
+        RW::write(self, value);
+        Ok(())
+    }
+}
+
+impl<T> TryWrite<T> for WO<T>
+    where T: Copy
+{
+    type Error = Infallible;
+
+    #[inline(always)]
+    unsafe fn try_write(&self, value: T) -> Result<(), Infallible> {
+        WO::write(self, value);
+        Ok(())
     }
 }
@@ -0,0 +1,220 @@
+use barrier;
+use poll::{Backoff, Timeout};
+use {Int, Read, Write};
+
+/// Mailbox between two cores: a data register, a status register with a
+/// "full" flag and, optionally, a register that interrupts the other core
+///
+/// The hardware sets the full flag when the data register is written and
+/// clears it when it's read. Sending makes the sender's preceding memory
+/// writes visible to the receiver before the message, so a message can
+/// refer to data in shared memory; receiving orders the receiver's
+/// following memory accesses after the message.
+///
+/// ``` no_run
+/// use volatile_register::poll::Spin;
+/// use volatile_register::{Mailbox, RO, RW, WO};
+///
+/// #[repr(C)]
+/// pub struct Ipc {
+///     pub data: RW<u32>,
+///     pub status: RO<u32>,
+///     pub irq_set: WO<u32>,
+/// }
+///
+/// const FULL: u32 = 1 << 0;
+///
+/// let ipc = unsafe { &*(0x4002_a000 as *const Ipc) };
+/// let mailbox = Mailbox::new(&ipc.data, &ipc.status, FULL).with_trigger(&ipc.irq_set, 1);
+///
+/// // on the application core
+/// unsafe { mailbox.send(0xcafe, 10_000, &mut Spin).unwrap() }
+///
+/// // on the network core, in the mailbox interrupt
+/// if let Ok(message) = mailbox.try_recv() {
+///     // ..
+/// }
+/// ```
+///
+/// The handshake, with the mailbox emulated in RAM:
+///
+/// ```
+/// use std::cell::Cell;
+///
+/// use volatile_register::poll::{Spin, Timeout};
+/// use volatile_register::{Mailbox, Read, WouldBlock, Write, WO};
+///
+/// const FULL: u32 = 1 << 0;
+///
+/// // Writing the data register sets the full flag; reading it clears it
+/// pub struct Data<'a> {
+///     message: Cell<u32>,
+///     status: &'a Cell<u32>,
+/// }
+///
+/// impl<'a> Write<u32> for Data<'a> {
+///     unsafe fn write(&self, message: u32) {
+///         self.message.set(message);
+///         self.status.set(FULL);
+///     }
+/// }
+///
+/// impl<'a> Read<u32> for Data<'a> {
+///     fn read(&self) -> u32 {
+///         self.status.set(0);
+///         self.message.get()
+///     }
+/// }
+///
+/// pub struct Status<'a>(&'a Cell<u32>);
+///
+/// impl<'a> Read<u32> for Status<'a> {
+///     fn read(&self) -> u32 {
+///         self.0.get()
+///     }
+/// }
+///
+/// let status = Cell::new(0);
+/// let data = Data { message: Cell::new(0), status: &status };
+/// let mut irq_set = 0u32;
+/// let trigger = unsafe { &*(&mut irq_set as *mut u32 as *const WO<u32>) };
+/// let sr = Status(&status);
+/// let mailbox = Mailbox::new(&data, &sr, FULL).with_trigger(trigger, 1);
+///
+/// // empty
+/// assert_eq!(mailbox.try_recv::<u32>(), Err(WouldBlock));
+/// assert_eq!(mailbox.recv::<u32, _>(3, &mut Spin), Err(Timeout { last: Some(0) }));
+///
+/// unsafe { mailbox.send(0xcafe, 3, &mut Spin).unwrap() }
+/// assert!(mailbox.is_full());
+/// assert_eq!(irq_set, 1);
+///
+/// // full
+/// assert_eq!(unsafe { mailbox.try_send(0xbeef) }, Err(WouldBlock));
+/// assert_eq!(unsafe { mailbox.send(0xbeef, 3, &mut Spin) }, Err(Timeout { last: Some(FULL) }));
+///
+/// assert_eq!(mailbox.recv(3, &mut Spin), Ok(0xcafe));
+/// assert!(!mailbox.is_full());
+///
+/// unsafe { mailbox.try_send(0xbeef).unwrap() }
+/// assert_eq!(mailbox.try_recv(), Ok(0xbeef));
+/// assert_eq!(mailbox.try_recv::<u32>(), Err(WouldBlock));
+/// ```
+pub struct Mailbox<'a, D, S, M>
+    where D: 'a, S: 'a
+{
+    data: &'a D,
+    status: &'a S,
+    full: M,
+    trigger: Option<(&'a dyn Write<M>, M)>,
+}
+
+/// Error returned by the non-blocking operations of a [`Mailbox`]: the
+/// mailbox is full (`try_send`) or empty (`try_recv`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WouldBlock;
+
+impl<'a, D, S, M> Mailbox<'a, D, S, M>
+    where S: Read<M>, M: Int
+{
+    /// Creates a mailbox out of the `data` register and the `status`
+    /// register, whose bits selected by `full` are set while a message is
+    /// pending
+    pub fn new(data: &'a D, status: &'a S, full: M) -> Self {
+        Mailbox { data, status, full, trigger: None }
+    }
+
+    /// Writes `kick` into `trigger` after every message sent, to interrupt
+    /// the receiver
+    pub fn with_trigger<R>(self, trigger: &'a R, kick: M) -> Self
+        where R: Write<M>
+    {
+        Mailbox { trigger: Some((trigger, kick)), ..self }
+    }
+
+    /// Returns `true` if a message is pending
+    #[inline(always)]
+    pub fn is_full(&self) -> bool {
+        self.status.read() & self.full != M::ZERO
+    }
+
+    /// Sends `message` if the mailbox is empty
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline]
+    pub unsafe fn try_send<T>(&self, message: T) -> Result<(), WouldBlock>
+        where D: Write<T>, T: Copy
+    {
+        if self.is_full() {
+            return Err(WouldBlock);
+        }
+        self.post(message);
+        Ok(())
+    }
+
+    /// Waits for the mailbox to be empty, then sends `message`
+    ///
+    /// Polls the status register at most `max_attempts` times, calling
+    /// `backoff` in between, like [`Read::wait_until`](trait.Read.html#method.wait_until).
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline]
+    pub unsafe fn send<T, B>(&self, message: T, max_attempts: usize, backoff: &mut B)
+                             -> Result<(), Timeout<M>>
+        where D: Write<T>, T: Copy, B: Backoff
+    {
+        let full = self.full;
+        self.status.wait_until(|status| status & full == M::ZERO, max_attempts, backoff)?;
+        self.post(message);
+        Ok(())
+    }
+
+    /// Receives the pending message, if any
+    #[inline]
+    pub fn try_recv<T>(&self) -> Result<T, WouldBlock>
+        where D: Read<T>, T: Copy
+    {
+        if !self.is_full() {
+            return Err(WouldBlock);
+        }
+        Ok(self.take())
+    }
+
+    /// Waits for a message, then receives it
+    ///
+    /// Polls the status register at most `max_attempts` times, calling
+    /// `backoff` in between, like [`Read::wait_until`](trait.Read.html#method.wait_until).
+    #[inline]
+    pub fn recv<T, B>(&self, max_attempts: usize, backoff: &mut B) -> Result<T, Timeout<M>>
+        where D: Read<T>, T: Copy, B: Backoff
+    {
+        let full = self.full;
+        self.status.wait_until(|status| status & full != M::ZERO, max_attempts, backoff)?;
+        Ok(self.take())
+    }
+
+    #[inline(always)]
+    unsafe fn post<T>(&self, message: T)
+        where D: Write<T>, T: Copy
+    {
+        barrier::write();
+        self.data.write(message);
+        if let Some((trigger, kick)) = self.trigger {
+            barrier::write();
+            trigger.write(kick);
+        }
+    }
+
+    #[inline(always)]
+    fn take<T>(&self) -> T
+        where D: Read<T>, T: Copy
+    {
+        let message = self.data.read();
+        barrier::full();
+        message
+    }
+}
@@ -0,0 +1,127 @@
+use core::marker::PhantomData;
+use core::mem;
+
+use access::{HasAccess, Kind};
+use {Int, WO};
+
+/// How a [`MaskedWO`] register encodes a masked write into a single word
+pub trait MaskEncoding<T>
+    where T: Int
+{
+    /// Returns the word that sets the bits selected by `mask` to the
+    /// corresponding bits of `value`, leaving the other bits untouched
+    fn encode(mask: T, value: T) -> T;
+}
+
+/// Bits to set in the lower half, bits to clear in the upper half
+///
+/// The encoding of STM32 `BSRR` registers: writing `1 << n` sets bit `n`,
+/// writing `1 << (n + 16)` clears it. Only masks that fit in the lower half of
+/// the word are meaningful.
+pub struct SetReset;
+
+impl<T> MaskEncoding<T> for SetReset
+    where T: Int
+{
+    #[inline(always)]
+    fn encode(mask: T, value: T) -> T {
+        (mask & value) | ((mask & !value) << (T::BITS / 2))
+    }
+}
+
+/// New value in the lower half, write-enable mask in the upper half
+///
+/// The encoding of the "hiword mask" registers of Rockchip and TI parts: bit
+/// `n` is only written if bit `n + 16` is set. Only masks that fit in the
+/// lower half of the word are meaningful.
+pub struct HighMask;
+
+impl<T> MaskEncoding<T> for HighMask
+    where T: Int
+{
+    #[inline(always)]
+    fn encode(mask: T, value: T) -> T {
+        (mask << (T::BITS / 2)) | (mask & value)
+    }
+}
+
+/// Write-only register that updates selected bits, without a
+/// read-modify-write, encoding the mask and the new values per `E`
+///
+/// ```
+/// use volatile_register::{MaskedWO, SetReset};
+///
+/// let mut memory = 0u32;
+/// let bsrr = unsafe { &*(&mut memory as *mut u32 as *const MaskedWO<u32, SetReset>) };
+///
+/// unsafe { bsrr.set(1 << 5) }
+/// assert_eq!(memory, 1 << 5);
+/// unsafe { bsrr.write_masked(0b11, 0b01) }
+/// assert_eq!(memory, (0b10 << 16) | 0b01);
+/// ```
+#[repr(transparent)]
+pub struct MaskedWO<T, E>
+    where T: Int
+{
+    register: WO<T>,
+    _encoding: PhantomData<E>,
+}
+
+#[cfg(feature = "emulation")]
+impl<T, E> Default for MaskedWO<T, E>
+    where T: Int + Default
+{
+    fn default() -> Self {
+        MaskedWO { register: WO::default(), _encoding: PhantomData }
+    }
+}
+
+impl<T, E> MaskedWO<T, E>
+    where T: Int, E: MaskEncoding<T>
+{
+    /// Returns the address of the register
+    #[inline(always)]
+    pub fn addr(&self) -> usize {
+        self.register.addr()
+    }
+
+    /// Sets the bits selected by `mask`
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn set(&self, mask: T) {
+        self.write_masked(mask, mask)
+    }
+
+    /// Clears the bits selected by `mask`
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn clear(&self, mask: T) {
+        self.write_masked(mask, T::ZERO)
+    }
+
+    /// Sets the bits selected by `mask` to the corresponding bits of `value`
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn write_masked(&self, mask: T, value: T) {
+        self.register.write(E::encode(mask, value))
+    }
+}
+
+impl<T, E> HasAccess for MaskedWO<T, E>
+    where T: Int
+{
+    const ACCESS: Option<Kind> = Some(Kind::WriteOnly);
+    const WIDTH: usize = mem::size_of::<T>();
+}
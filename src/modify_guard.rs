@@ -0,0 +1,69 @@
+use RW;
+
+impl<T> RW<T>
+    where T: Copy
+{
+    /// Performs a read-modify-write operation that is undone when the
+    /// returned guard is dropped
+    ///
+    /// The guard writes back the value read before the modification, so the
+    /// temporary state, like a masked interrupt or an alternative clock
+    /// source, is left on every path out of the scope, early returns
+    /// included.
+    ///
+    /// ```
+    /// use volatile_register::RW;
+    ///
+    /// let mut memory = 0b11u32;
+    /// let ier = unsafe { &*(&mut memory as *mut u32 as *const RW<u32>) };
+    ///
+    /// {
+    ///     let _rx_masked = unsafe { ier.scoped_modify(|ier| ier & !0b01) };
+    ///     assert_eq!(ier.read(), 0b10);
+    /// }
+    /// assert_eq!(ier.read(), 0b11);
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful; note that
+    /// the restoring write also happens when the guard is dropped while
+    /// unwinding
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn scoped_modify<F>(&self, f: F) -> ModifyGuard<'_, T>
+        where F: FnOnce(T) -> T
+    {
+        let previous = self.read();
+        self.write(f(previous));
+        ModifyGuard { register: self, previous }
+    }
+}
+
+/// Restores the value a register held before a
+/// [`scoped_modify`](struct.RW.html#method.scoped_modify) when dropped
+#[must_use = "the modification is undone as soon as the guard is dropped"]
+pub struct ModifyGuard<'a, T>
+    where T: Copy
+{
+    register: &'a RW<T>,
+    previous: T,
+}
+
+impl<'a, T> ModifyGuard<'a, T>
+    where T: Copy
+{
+    /// Returns the value the register held before the modification
+    pub fn previous(&self) -> T {
+        self.previous
+    }
+}
+
+impl<'a, T> Drop for ModifyGuard<'a, T>
+    where T: Copy
+{
+    #[inline(always)]
+    fn drop(&mut self) {
+        unsafe { self.register.write(self.previous) }
+    }
+}
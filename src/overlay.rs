@@ -0,0 +1,144 @@
+use core::marker::PhantomData;
+use core::ops::Deref;
+
+/// Address range shared by two register block layouts
+///
+/// Some addresses host one of several peripherals depending on a
+/// system-level mode (a SERCOM configured as UART or as SPI, for example).
+/// The `Overlay` is the token for the address range: it has to be exchanged
+/// for an [`OverlayView`] of one of the layouts, and the view has to be
+/// released to get the token back, so the two layouts can never be accessed
+/// at the same time.
+///
+/// ``` no_run
+/// use volatile_register::{Overlay, RW};
+///
+/// #[repr(C)]
+/// pub struct Uart {
+///     pub ctrl: RW<u32>,
+///     pub baud: RW<u32>,
+/// }
+///
+/// #[repr(C)]
+/// pub struct Spi {
+///     pub ctrl: RW<u32>,
+///     pub data: RW<u32>,
+/// }
+///
+/// let sercom0 = unsafe { Overlay::<Uart, Spi>::new(0x4200_0800) };
+///
+/// // .. configure SERCOM0 as UART ..
+/// let uart = sercom0.into_a();
+/// unsafe { uart.baud.write(63019) }
+///
+/// // .. and later as SPI
+/// let sercom0 = uart.release();
+/// let spi = sercom0.into_b();
+/// unsafe { spi.data.write(0x9f) }
+/// ```
+///
+/// Both layouts reach the same memory:
+///
+/// ```
+/// use volatile_register::{Overlay, RW};
+///
+/// #[repr(C)]
+/// pub struct Uart {
+///     pub ctrl: RW<u32>,
+///     pub baud: RW<u32>,
+/// }
+///
+/// #[repr(C)]
+/// pub struct Spi {
+///     pub ctrl: RW<u32>,
+///     pub data: RW<u32>,
+/// }
+///
+/// let mut memory = [0u32; 2];
+/// let sercom0 = unsafe { Overlay::<Uart, Spi>::new(memory.as_mut_ptr() as usize) };
+///
+/// let uart = sercom0.into_a();
+/// unsafe {
+///     uart.ctrl.write(1);
+///     uart.baud.write(63019);
+/// }
+///
+/// let spi = uart.release().into_b();
+/// assert_eq!(spi.ctrl.read(), 1);
+/// assert_eq!(spi.data.read(), 63019);
+/// unsafe { spi.data.write(0x9f) }
+///
+/// let uart = spi.release().into_a();
+/// assert_eq!(uart.baud.read(), 0x9f);
+/// drop(uart);
+/// assert_eq!(memory, [1, 0x9f]);
+/// ```
+///
+/// Taking the second view consumes the token the first one holds:
+///
+/// ``` compile_fail
+/// use volatile_register::{Overlay, RW};
+///
+/// #[repr(C)]
+/// pub struct Uart {
+///     pub baud: RW<u32>,
+/// }
+///
+/// #[repr(C)]
+/// pub struct Spi {
+///     pub data: RW<u32>,
+/// }
+///
+/// let sercom0 = unsafe { Overlay::<Uart, Spi>::new(0x4200_0800) };
+///
+/// let uart = sercom0.into_a();
+/// let spi = sercom0.into_b();
+/// unsafe { uart.baud.write(63019) }
+/// ```
+pub struct Overlay<A, B> {
+    address: usize,
+    _marker: PhantomData<(*const A, *const B)>,
+}
+
+impl<A, B> Overlay<A, B> {
+    /// Creates the token for the address range starting at `address`
+    ///
+    /// # Safety
+    ///
+    /// Both `A` and `B` must describe the registers found at `address`, and
+    /// only one `Overlay` must exist for the address range
+    pub const unsafe fn new(address: usize) -> Self {
+        Overlay { address, _marker: PhantomData }
+    }
+
+    /// Exchanges the token for a view of the `A` layout
+    pub fn into_a(self) -> OverlayView<A, B, A> {
+        OverlayView { overlay: self, _marker: PhantomData }
+    }
+
+    /// Exchanges the token for a view of the `B` layout
+    pub fn into_b(self) -> OverlayView<A, B, B> {
+        OverlayView { overlay: self, _marker: PhantomData }
+    }
+}
+
+/// OverlayView of an [`Overlay`] through the `L` layout
+pub struct OverlayView<A, B, L> {
+    overlay: Overlay<A, B>,
+    _marker: PhantomData<*const L>,
+}
+
+impl<A, B, L> OverlayView<A, B, L> {
+    /// Gives up the view, returning the token
+    pub fn release(self) -> Overlay<A, B> {
+        self.overlay
+    }
+}
+
+impl<A, B, L> Deref for OverlayView<A, B, L> {
+    type Target = L;
+
+    fn deref(&self) -> &L {
+        unsafe { &*(self.overlay.address as *const L) }
+    }
+}
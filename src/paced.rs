@@ -0,0 +1,163 @@
+use core::convert::Infallible;
+
+use poll::{Backoff, Timeout};
+use {Int, Read, Write};
+
+/// How a [`Paced`] register waits before a write
+///
+/// Any `FnMut()`, like the `delay_us` of a HAL, is a `Pace` that can't fail.
+pub trait Pace {
+    /// Error reported when the register didn't become writable
+    type Error;
+
+    /// Returns once the register can be written again
+    fn wait(&mut self) -> Result<(), Self::Error>;
+}
+
+impl<F> Pace for F
+    where F: FnMut()
+{
+    type Error = Infallible;
+
+    #[inline(always)]
+    fn wait(&mut self) -> Result<(), Infallible> {
+        self();
+        Ok(())
+    }
+}
+
+/// Waits for the bits selected by `mask` of the `status` register to read
+/// as zero
+///
+/// For registers that report the synchronization of the previous write,
+/// like `RTC.SYNCBUSY`. The status register is polled like
+/// [`Read::wait_until`](trait.Read.html#method.wait_until) does, at most
+/// `max_attempts` times, calling `backoff` in between.
+pub struct SyncBusy<'a, S, T, B> {
+    status: &'a S,
+    mask: T,
+    max_attempts: usize,
+    backoff: B,
+}
+
+impl<'a, S, T, B> SyncBusy<'a, S, T, B>
+    where S: Read<T>, T: Int, B: Backoff
+{
+    /// Polls `status`, at most `max_attempts` times, until the bits selected
+    /// by `mask` are clear
+    pub fn new(status: &'a S, mask: T, max_attempts: usize, backoff: B) -> Self {
+        SyncBusy { status, mask, max_attempts, backoff }
+    }
+}
+
+impl<'a, S, T, B> Pace for SyncBusy<'a, S, T, B>
+    where S: Read<T>, T: Int, B: Backoff
+{
+    type Error = Timeout<T>;
+
+    #[inline]
+    fn wait(&mut self) -> Result<(), Timeout<T>> {
+        let mask = self.mask;
+        self.status
+            .wait_until(|status| status & mask == T::ZERO, self.max_attempts, &mut self.backoff)
+            .map(|_| ())
+    }
+}
+
+/// Register that needs some time between consecutive writes
+///
+/// Registers in a slow clock domain, like RTC or backup domain registers,
+/// drop or corrupt writes that follow the previous one too closely. `Paced`
+/// waits with `P` before each write, and doesn't write if the wait fails.
+///
+/// ```
+/// use volatile_register::poll::{Spin, Timeout};
+/// use volatile_register::{Paced, SyncBusy, RO, RW};
+///
+/// #[repr(C)]
+/// pub struct Rtc {
+///     pub count: RW<u32>,
+///     pub compare: RW<u32>,
+///     pub syncbusy: RO<u32>,
+/// }
+///
+/// const COMP0: u32 = 1 << 5;
+///
+/// let mut memory = [0u32; 3];
+/// let memory = memory.as_mut_ptr();
+/// let rtc = unsafe { &*(memory as *const Rtc) };
+///
+/// let mut compare = Paced::new(&rtc.compare, SyncBusy::new(&rtc.syncbusy, COMP0, 100, Spin));
+/// unsafe {
+///     compare.write(32_768).unwrap();
+///     compare.modify(|compare| compare + 32_768).unwrap();
+/// }
+/// assert_eq!(rtc.compare.read(), 65_536);
+///
+/// // the previous write never synchronizes: the next one is not performed
+/// unsafe { *memory.add(2) = COMP0 }
+/// assert_eq!(unsafe { compare.write(0) }, Err(Timeout { last: Some(COMP0) }));
+/// assert_eq!(rtc.compare.read(), 65_536);
+///
+/// // or with a delay
+/// let mut count = Paced::new(&rtc.count, || { /* delay 3 cycles of the 32 kHz clock */ });
+/// unsafe { count.write(1).unwrap() }
+/// assert_eq!(rtc.count.read(), 1);
+/// ```
+pub struct Paced<'a, R, P> {
+    register: &'a R,
+    pace: P,
+}
+
+impl<'a, R, P> Paced<'a, R, P>
+    where P: Pace
+{
+    /// Paces the writes to `register` with `pace`
+    pub fn new(register: &'a R, pace: P) -> Self {
+        Paced { register, pace }
+    }
+
+    /// Reads the value of the register
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub fn read<T>(&self) -> T
+        where R: Read<T>, T: Copy
+    {
+        self.register.read()
+    }
+
+    /// Waits, then writes a `value` into the register
+    ///
+    /// Returns the error of the wait, without writing, if it failed.
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn write<T>(&mut self, value: T) -> Result<(), P::Error>
+        where R: Write<T>, T: Copy
+    {
+        self.pace.wait()?;
+        self.register.write(value);
+        Ok(())
+    }
+
+    /// Waits, then performs a read-modify-write operation
+    ///
+    /// Returns the error of the wait, without accessing the register, if it
+    /// failed.
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn modify<T, F>(&mut self, f: F) -> Result<(), P::Error>
+        where R: Read<T> + Write<T>, T: Copy, F: FnOnce(T) -> T
+    {
+        self.pace.wait()?;
+        self.register.write(f(self.register.read()));
+        Ok(())
+    }
+}
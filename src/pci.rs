@@ -0,0 +1,125 @@
+//! PCI(e) BAR mapping for userspace drivers on Linux
+//!
+//! [`Bar`] maps a register block exposed through a PCI Base Address Register
+//! into the address space of the process, either from a sysfs resource file
+//! (`/sys/bus/pci/devices/<address>/resource<N>`) or from a region of a VFIO
+//! device file descriptor.
+//!
+//! Registers must not be mapped write-combined: the `resource<N>_wc` sysfs
+//! files are rejected, and the regular resource files are mapped uncached by
+//! the kernel.
+//!
+//! ``` no_run
+//! use volatile_register::pci::Bar;
+//! use volatile_register::{RO, RW};
+//!
+//! #[repr(C)]
+//! pub struct Regs {
+//!     pub id: RO<u32>,
+//!     pub ctrl: RW<u32>,
+//! }
+//!
+//! let bar = unsafe { Bar::<Regs>::open("/sys/bus/pci/devices/0000:01:00.0/resource0") }
+//!     .unwrap();
+//! println!("device id: {:#x}", bar.id.read());
+//! ```
+
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::marker::PhantomData;
+use std::mem;
+use std::ops::Deref;
+use std::os::raw::{c_int, c_void};
+use std::os::unix::io::AsRawFd;
+use std::path::Path;
+
+const PROT_READ: c_int = 1;
+const PROT_WRITE: c_int = 2;
+const MAP_SHARED: c_int = 1;
+
+extern "C" {
+    fn mmap(addr: *mut c_void,
+            len: usize,
+            prot: c_int,
+            flags: c_int,
+            fd: c_int,
+            offset: i64)
+            -> *mut c_void;
+    fn munmap(addr: *mut c_void, len: usize) -> c_int;
+}
+
+/// Register block `T` mapped from a PCI BAR
+///
+/// The mapping is removed when the `Bar` is dropped.
+pub struct Bar<T> {
+    address: *mut c_void,
+    _marker: PhantomData<T>,
+}
+
+impl<T> Bar<T> {
+    /// Maps the BAR exposed by the sysfs resource file at `path`
+    ///
+    /// Fails if the file is a write-combined (`_wc`) resource file, or if
+    /// the BAR is smaller than `T`.
+    ///
+    /// # Safety
+    ///
+    /// The BAR must contain a register block laid out as `T`
+    pub unsafe fn open<P>(path: P) -> io::Result<Self>
+        where P: AsRef<Path>
+    {
+        let path = path.as_ref();
+        if path.to_string_lossy().ends_with("_wc") {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "registers can't be mapped write-combined"));
+        }
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        if file.metadata()?.len() < mem::size_of::<T>() as u64 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                      "BAR is smaller than the register block"));
+        }
+        Bar::map(&file, 0)
+    }
+
+    /// Maps the register block located at `offset` in `file`
+    ///
+    /// For VFIO, `file` is the device file descriptor and `offset` the
+    /// offset of the BAR region reported by `VFIO_DEVICE_GET_REGION_INFO`.
+    /// `offset` must be a multiple of the page size. The mapping stays valid
+    /// after `file` is closed.
+    ///
+    /// # Safety
+    ///
+    /// `file` must contain a register block laid out as `T` at `offset`
+    pub unsafe fn map(file: &File, offset: u64) -> io::Result<Self> {
+        if offset > i64::MAX as u64 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "offset out of range"));
+        }
+        let address = mmap(std::ptr::null_mut(),
+                           mem::size_of::<T>(),
+                           PROT_READ | PROT_WRITE,
+                           MAP_SHARED,
+                           file.as_raw_fd(),
+                           offset as i64);
+        if address as isize == -1 {
+            return Err(io::Error::last_os_error());
+        }
+        Ok(Bar { address, _marker: PhantomData })
+    }
+}
+
+impl<T> Deref for Bar<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        unsafe { &*(self.address as *const T) }
+    }
+}
+
+impl<T> Drop for Bar<T> {
+    fn drop(&mut self) {
+        unsafe {
+            munmap(self.address, mem::size_of::<T>());
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use core::marker::PhantomData;
+use core::ops::Deref;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Owned handle to the register block `T` located at address `BASE`
+///
+/// A handle is obtained once, with [`take`](#method.take), and then passed
+/// to the driver that owns the peripheral. [`steal`](#method.steal) bypasses
+/// the check for code that runs after the ownership machinery can no longer
+/// be trusted, like fault handlers.
+///
+/// `Peripheral`, `TakeOnce` and [`peripherals!`](macro.peripherals.html)
+/// only exist on targets with 8-bit atomic swaps, and on the targets where
+/// `interrupt::free` can make the flag update atomic. They are missing on
+/// targets with neither, like MSP430.
+///
+/// ``` no_run
+/// use volatile_register::{Peripheral, TakeOnce, RW};
+///
+/// #[repr(C)]
+/// pub struct Gpio {
+///     pub odr: RW<u32>,
+/// }
+///
+/// pub type Gpioa = Peripheral<Gpio, 0x4800_0000>;
+/// static GPIOA: TakeOnce = TakeOnce::new();
+///
+/// // `GPIOA` is the only flag used to take a `Gpioa`
+/// let gpioa = unsafe { Gpioa::take(&GPIOA) }.unwrap();
+/// assert!(unsafe { Gpioa::take(&GPIOA) }.is_none());
+/// unsafe { gpioa.odr.write(1 << 5) }
+/// ```
+pub struct Peripheral<T, const BASE: usize> {
+    _marker: PhantomData<*const T>,
+}
+
+unsafe impl<T, const BASE: usize> Send for Peripheral<T, BASE> {}
+
+impl<T, const BASE: usize> Peripheral<T, BASE> {
+    /// Address of the register block
+    pub const ADDRESS: usize = BASE;
+
+    /// Returns the handle the first time `taken` is used; `None` afterwards
+    ///
+    /// # Safety
+    ///
+    /// `taken` must be the only flag ever used to take this peripheral, the
+    /// one [`peripherals!`](macro.peripherals.html) defines for it if it's
+    /// listed there: taking it through two different flags hands out two
+    /// owners
+    #[inline]
+    pub unsafe fn take(taken: &'static TakeOnce) -> Option<Self> {
+        if taken.take() {
+            Some(Self::steal())
+        } else {
+            None
+        }
+    }
+
+    /// Returns a handle without checking whether one already exists
+    ///
+    /// Meant for HardFault handlers, panic reporters and similar post-crash
+    /// code that needs to reach registers, e.g. to dump a fault status
+    /// register or to put outputs in a safe state, regardless of which
+    /// driver owned the peripheral.
+    ///
+    /// # Safety
+    ///
+    /// The handle aliases the one returned by `take`, if any: the caller
+    /// must make sure that the accesses performed through it don't
+    /// interfere with the accesses of the owner
+    #[inline(always)]
+    pub const unsafe fn steal() -> Self {
+        Peripheral { _marker: PhantomData }
+    }
+
+    /// Returns a raw pointer to the register block
+    #[inline(always)]
+    pub const fn ptr() -> *const T {
+        BASE as *const T
+    }
+}
+
+impl<T, const BASE: usize> Deref for Peripheral<T, BASE> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &T {
+        unsafe { &*Self::ptr() }
+    }
+}
+
+/// Flag that can be taken once, guarding a [`Peripheral`]
+pub struct TakeOnce {
+    taken: AtomicBool,
+}
+
+impl TakeOnce {
+    /// Creates a flag that hasn't been taken yet
+    pub const fn new() -> Self {
+        TakeOnce { taken: AtomicBool::new(false) }
+    }
+
+    /// Takes the flag, returning `true` if it hadn't been taken before
+    #[cfg(target_has_atomic = "8")]
+    #[inline]
+    pub fn take(&self) -> bool {
+        !self.taken.swap(true, Ordering::AcqRel)
+    }
+
+    cfg_free! {
+        /// Takes the flag, returning `true` if it hadn't been taken before
+        #[cfg(not(target_has_atomic = "8"))]
+        #[inline]
+        pub fn take(&self) -> bool {
+            ::interrupt::free(|_| {
+                let taken = self.taken.load(Ordering::Acquire);
+                self.taken.store(true, Ordering::Release);
+                !taken
+            })
+        }
+    }
+
+    /// Gives the flag back, so that it can be taken again
+    ///
+    /// # Safety
+    ///
+    /// The caller must have taken the flag, and not handed out what it
+    /// guards
+    #[doc(hidden)]
+    #[inline]
+    pub unsafe fn __release(&self) {
+        self.taken.store(false, Ordering::Release)
+    }
+}
+
+impl Default for TakeOnce {
+    fn default() -> Self {
+        TakeOnce::new()
+    }
+}
+
+/// Defines a `struct` holding a [`Peripheral`] handle for each listed
+/// register block, with a global `take` that hands them out once
+///
+/// Each peripheral gets its `TakeOnce` flag, as an associated constant named
+/// after the field: the global `take` takes all of them, and a single
+/// peripheral can be taken on its own by passing its flag to
+/// [`Peripheral::take`]. Once a flag is taken the global `take` returns
+/// `None`, without keeping any of the other flags.
+///
+/// ```
+/// #[macro_use]
+/// extern crate volatile_register;
+///
+/// use volatile_register::RW;
+///
+/// #[repr(C)]
+/// pub struct Gpio {
+///     pub odr: RW<u32>,
+/// }
+///
+/// #[repr(C)]
+/// pub struct Usart {
+///     pub dr: RW<u32>,
+/// }
+///
+/// peripherals! {
+///     /// All the peripherals of the device
+///     pub struct Peripherals {
+///         pub GPIOA: Gpio = 0x4800_0000,
+///         pub GPIOB: Gpio = 0x4800_0400,
+///         pub USART1: Usart = 0x4001_3800,
+///     }
+/// }
+///
+/// fn main() {
+///     let p = Peripherals::take().unwrap();
+///     assert!(Peripherals::take().is_none());
+///
+///     let _gpioa = p.GPIOA;
+///     let _usart1 = p.USART1;
+///
+///     // the handles share their flags with the global `take`
+///     type Gpiob = volatile_register::Peripheral<Gpio, 0x4800_0400>;
+///     assert!(unsafe { Gpiob::take(Peripherals::GPIOB) }.is_none());
+/// }
+/// ```
+///
+/// A failed global `take` leaves the flags of the other peripherals free:
+///
+/// ```
+/// #[macro_use]
+/// extern crate volatile_register;
+///
+/// use volatile_register::{Peripheral, RW};
+///
+/// #[repr(C)]
+/// pub struct Gpio {
+///     pub odr: RW<u32>,
+/// }
+///
+/// #[repr(C)]
+/// pub struct Usart {
+///     pub dr: RW<u32>,
+/// }
+///
+/// peripherals! {
+///     pub struct Peripherals {
+///         pub GPIOA: Gpio = 0x4800_0000,
+///         pub GPIOB: Gpio = 0x4800_0400,
+///         pub USART1: Usart = 0x4001_3800,
+///     }
+/// }
+///
+/// fn main() {
+///     type Gpioa = Peripheral<Gpio, 0x4800_0000>;
+///     type Gpiob = Peripheral<Gpio, 0x4800_0400>;
+///     type Usart1 = Peripheral<Usart, 0x4001_3800>;
+///
+///     let _gpiob = unsafe { Gpiob::take(Peripherals::GPIOB) }.unwrap();
+///     assert!(Peripherals::take().is_none());
+///
+///     // the flags before and after `GPIOB` can still be taken
+///     assert!(unsafe { Gpioa::take(Peripherals::GPIOA) }.is_some());
+///     assert!(unsafe { Usart1::take(Peripherals::USART1) }.is_some());
+/// }
+/// ```
+#[macro_export]
+macro_rules! peripherals {
+    ($(#[$attr:meta])*
+     $vis:vis struct $name:ident {
+         $(
+             $(#[$field_attr:meta])*
+             $field_vis:vis $field:ident: $ty:ty = $base:expr
+         ),* $(,)?
+     }) => {
+        $(#[$attr])*
+        #[allow(non_snake_case)]
+        $vis struct $name {
+            $(
+                $(#[$field_attr])*
+                $field_vis $field: $crate::Peripheral<$ty, { $base }>,
+            )*
+        }
+
+        #[allow(non_upper_case_globals)]
+        impl $name {
+            $(
+                /// Flag guarding this peripheral, for `Peripheral::take`
+                $field_vis const $field: &'static $crate::TakeOnce = {
+                    static TAKEN: $crate::TakeOnce = $crate::TakeOnce::new();
+                    &TAKEN
+                };
+            )*
+
+            /// Returns all the peripherals the first time it's called;
+            /// `None` afterwards, or if any of them was taken on its own
+            #[inline]
+            pub fn take() -> Option<Self> {
+                let flags: &[&'static $crate::TakeOnce] = &[$(Self::$field),*];
+                for (i, flag) in flags.iter().enumerate() {
+                    if !flag.take() {
+                        // give back the flags taken by this call
+                        for taken in &flags[..i] {
+                            unsafe { taken.__release() }
+                        }
+                        return None;
+                    }
+                }
+
+                Some(unsafe { Self::steal() })
+            }
+
+            /// Returns all the peripherals without checking whether they
+            /// were already taken
+            ///
+            /// # Safety
+            ///
+            /// See `volatile_register::Peripheral::steal`
+            #[inline(always)]
+            pub unsafe fn steal() -> Self {
+                $name {
+                    $(
+                        $field: $crate::Peripheral::steal(),
+                    )*
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! Polling
+
+/// What to do between two polls of a register
+///
+/// Bare-metal code usually just spins; code running under an RTOS can yield
+/// to other tasks, or sleep, instead of burning CPU time. Any `FnMut()` is a
+/// `Backoff`, which makes it easy to plug in the RTOS's yield function.
+pub trait Backoff {
+    /// Called after a poll that didn't succeed, before the next one
+    fn backoff(&mut self);
+}
+
+impl<F> Backoff for F
+    where F: FnMut()
+{
+    #[inline(always)]
+    fn backoff(&mut self) {
+        self()
+    }
+}
+
+/// Busy waits, hinting the processor that it's spinning
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Spin;
+
+impl Backoff for Spin {
+    #[inline(always)]
+    fn backoff(&mut self) {
+        core::hint::spin_loop()
+    }
+}
+
+/// Sleeps until an event (`WFE`)
+///
+/// The peripheral, or another core, has to signal an event when the polled
+/// register changes, or the wait can last forever without an interrupt.
+///
+/// Available on bare-metal ARMv6K and later, M-profile included, and
+/// AArch64 targets; older ARM cores have no `WFE` instruction. Hosted
+/// targets don't get it: user-mode code may not be allowed to sleep the
+/// core.
+#[cfg(all(target_os = "none",
+          any(all(target_arch = "arm", any(target_feature = "v6k", target_feature = "mclass")),
+              target_arch = "aarch64")))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Wfe;
+
+#[cfg(all(target_os = "none",
+          any(all(target_arch = "arm", any(target_feature = "v6k", target_feature = "mclass")),
+              target_arch = "aarch64")))]
+impl Backoff for Wfe {
+    #[inline(always)]
+    fn backoff(&mut self) {
+        unsafe { core::arch::asm!("wfe", options(nomem, nostack, preserves_flags)) }
+    }
+}
+
+/// Sleeps until an interrupt (`WFI`)
+///
+/// The polled register has to change from an interrupt handler, or along
+/// with an interrupt, or the wait can last forever.
+///
+/// Available on the same bare-metal ARM targets as `Wfe`, and on bare-metal
+/// RISC-V; `wfi` traps in RISC-V user mode.
+#[cfg(all(target_os = "none",
+          any(all(target_arch = "arm", any(target_feature = "v6k", target_feature = "mclass")),
+              target_arch = "aarch64",
+              target_arch = "riscv32",
+              target_arch = "riscv64")))]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Wfi;
+
+#[cfg(all(target_os = "none",
+          any(all(target_arch = "arm", any(target_feature = "v6k", target_feature = "mclass")),
+              target_arch = "aarch64",
+              target_arch = "riscv32",
+              target_arch = "riscv64")))]
+impl Backoff for Wfi {
+    #[inline(always)]
+    fn backoff(&mut self) {
+        unsafe { core::arch::asm!("wfi", options(nomem, nostack, preserves_flags)) }
+    }
+}
+
+/// Error returned when a register didn't reach the expected state in the
+/// given number of polls
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Timeout<T> {
+    /// The last value read from the register, or `None` if no poll was
+    /// allowed (`max_attempts` was 0)
+    pub last: Option<T>,
+}
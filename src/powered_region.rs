@@ -0,0 +1,102 @@
+use core::marker::PhantomData;
+
+/// Register block `T` in a power domain that has to be on before the block
+/// is accessed
+///
+/// Accessing the registers of a gated power domain, or of a peripheral
+/// whose clock is off, faults the bus. A `PoweredRegion` only hands out
+/// references to the block in exchange for a borrow of a token of type `P`,
+/// which the power management driver creates when it turns the domain on and
+/// consumes when it turns it off. The references can't outlive the borrow,
+/// so the registers can't be accessed once the domain is off.
+///
+/// ```
+/// use volatile_register::{PoweredRegion, RW};
+///
+/// #[repr(C)]
+/// pub struct Usb {
+///     pub ctrl: RW<u32>,
+/// }
+///
+/// /// Proof that the USB power domain is on
+/// pub struct UsbPowered {
+///     _private: (),
+/// }
+///
+/// pub struct Pmu;
+///
+/// impl Pmu {
+///     pub fn power_on(&mut self) -> UsbPowered {
+///         // .. enable the domain, wait for it to be up ..
+///         UsbPowered { _private: () }
+///     }
+///
+///     pub fn power_off(&mut self, _: UsbPowered) {
+///         // .. disable the domain ..
+///     }
+/// }
+///
+/// let mut memory = 0u32;
+/// let usb = unsafe { PoweredRegion::<Usb, UsbPowered>::new(&mut memory as *mut u32 as usize) };
+/// let mut pmu = Pmu;
+///
+/// let powered = pmu.power_on();
+/// unsafe { usb.get(&powered).ctrl.write(1) }
+/// assert_eq!(usb.get(&powered).ctrl.read(), 1);
+/// pmu.power_off(powered);
+/// ```
+///
+/// Using the registers after the domain has been turned off is a compile
+/// time error:
+///
+/// ``` compile_fail
+/// use volatile_register::{PoweredRegion, RW};
+///
+/// pub struct UsbPowered;
+///
+/// fn power_off(_: UsbPowered) {}
+///
+/// let usb = unsafe { PoweredRegion::<RW<u32>, UsbPowered>::new(0x5000_0000) };
+/// let powered = UsbPowered;
+/// let ctrl = usb.get(&powered);
+/// power_off(powered);
+/// unsafe { ctrl.write(1) }
+/// ```
+pub struct PoweredRegion<T, P> {
+    address: usize,
+    _marker: PhantomData<(*const T, *const P)>,
+}
+
+unsafe impl<T, P> Send for PoweredRegion<T, P> {}
+
+impl<T, P> PoweredRegion<T, P> {
+    /// Describes the block `T` located at `address`, in the power domain
+    /// whose tokens are of type `P`
+    ///
+    /// # Safety
+    ///
+    /// `T` must describe the registers found at `address`, and a `P` must
+    /// only exist while the domain is on
+    pub const unsafe fn new(address: usize) -> Self {
+        PoweredRegion { address, _marker: PhantomData }
+    }
+
+    /// Returns the address of the register block
+    pub fn addr(&self) -> usize {
+        self.address
+    }
+
+    /// Returns the register block, for as long as `powered` is borrowed
+    #[inline(always)]
+    pub fn get<'p>(&self, _powered: &'p P) -> &'p T {
+        unsafe { &*(self.address as *const T) }
+    }
+
+    /// Runs `f` on the register block
+    #[inline(always)]
+    pub fn with<F, R>(&self, powered: &P, f: F) -> R
+        where F: FnOnce(&T) -> R
+    {
+        f(self.get(powered))
+    }
+}
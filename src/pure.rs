@@ -0,0 +1,129 @@
+use core::fmt;
+use core::ops::Deref;
+
+use access::{HasAccess, Kind};
+use {Read, RO, RW};
+
+/// Marks a register whose reads have no side effects
+///
+/// Reading some registers changes the state of the peripheral: it pops a
+/// FIFO or clears status flags. Diagnostics, like the `Debug`
+/// implementation, must never do that, so they only read registers wrapped
+/// in `Pure`, through the [`PureRead`] trait.
+///
+/// `Pure` dereferences to the wrapped register, so it can still be used as
+/// usual.
+///
+/// ```
+/// use volatile_register::{Pure, RO, RW};
+///
+/// #[repr(C)]
+/// pub struct Uart {
+///     pub cr: Pure<RW<u32>>,
+///     // reading the data register pops the RX FIFO
+///     pub dr: RO<u32>,
+/// }
+///
+/// let mut memory = [0x0000_0301_u32, 0];
+/// let uart = unsafe { &*(memory.as_mut_ptr() as *const Uart) };
+///
+/// assert_eq!(format!("{:?}", uart.cr), "RW(769)");
+/// assert_eq!(uart.dr.read(), 0);
+/// ```
+#[repr(transparent)]
+pub struct Pure<R> {
+    register: R,
+}
+
+#[cfg(feature = "emulation")]
+impl<R> Default for Pure<R>
+    where R: Default
+{
+    fn default() -> Self {
+        Pure { register: R::default() }
+    }
+}
+
+impl<R> HasAccess for Pure<R>
+    where R: HasAccess
+{
+    const ACCESS: Option<Kind> = R::ACCESS;
+    const WIDTH: usize = R::WIDTH;
+    const PURE: bool = true;
+    const SPLIT: bool = R::SPLIT;
+}
+
+impl<R> Deref for Pure<R> {
+    type Target = R;
+
+    fn deref(&self) -> &R {
+        &self.register
+    }
+}
+
+/// Registers whose reads have no side effects
+///
+/// Implemented by [`Pure`] registers. Generic diagnostics, like register
+/// dumps, should use this trait rather than [`Read`].
+pub trait PureRead<T>
+    where T: Copy
+{
+    /// Reads the value of the register, without side effects
+    fn peek(&self) -> T;
+}
+
+impl<T> PureRead<T> for Pure<RO<T>>
+    where T: Copy
+{
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    fn peek(&self) -> T {
+        self.register.read()
+    }
+}
+
+impl<T> PureRead<T> for Pure<RW<T>>
+    where T: Copy
+{
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    fn peek(&self) -> T {
+        self.register.read()
+    }
+}
+
+impl<T> Read<T> for Pure<RO<T>>
+    where T: Copy
+{
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    fn read(&self) -> T {
+        self.register.read()
+    }
+}
+
+impl<T> Read<T> for Pure<RW<T>>
+    where T: Copy
+{
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    fn read(&self) -> T {
+        self.register.read()
+    }
+}
+
+impl<T> fmt::Debug for Pure<RO<T>>
+    where T: Copy + fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("RO").field(&self.peek()).finish()
+    }
+}
+
+impl<T> fmt::Debug for Pure<RW<T>>
+    where T: Copy + fmt::Debug
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("RW").field(&self.peek()).finish()
+    }
+}
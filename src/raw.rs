@@ -0,0 +1,153 @@
+//! Volatile accesses
+//!
+//! Every register access performed by this crate goes through `read` and
+//! `write` so that alternative access paths, like the `indirect`, `wasm`,
+//! `stats` and `timing` features, only have to be hooked in one place.
+//!
+//! `read` and `write` also reject, at compile time, accesses wider than the
+//! target's native access width; `read_torn` and `write_torn` are the
+//! unchecked versions used by `Torn`.
+
+use core::marker::PhantomData;
+use core::mem;
+#[cfg(feature = "timing")]
+use core::panic::Location;
+
+#[cfg(all(feature = "wasm", target_arch = "wasm32"))]
+use wasm as backend;
+
+#[cfg(all(feature = "indirect", not(all(feature = "wasm", target_arch = "wasm32"))))]
+use indirect as backend;
+
+#[cfg(not(any(feature = "indirect", all(feature = "wasm", target_arch = "wasm32"))))]
+mod backend {
+    pub use core::ptr::{read_volatile as read, write_volatile as write};
+}
+
+/// Widest access, in bytes, that the target performs as a single bus
+/// transaction
+#[cfg(target_pointer_width = "16")]
+pub const NATIVE_WIDTH: usize = 2;
+#[cfg(target_pointer_width = "32")]
+pub const NATIVE_WIDTH: usize = 4;
+#[cfg(target_pointer_width = "64")]
+pub const NATIVE_WIDTH: usize = 8;
+
+struct Native<T>(PhantomData<T>);
+
+impl<T> Native<T> {
+    const CHECK: () = assert!(mem::size_of::<T>() <= NATIVE_WIDTH,
+                              "register wider than the native access width; \
+                               wrap it in `Torn` to allow torn accesses");
+}
+
+/// Performs a volatile read of the value `src` points to
+#[inline(always)]
+#[cfg_attr(feature = "timing", track_caller)]
+pub unsafe fn read<T>(src: *const T) -> T
+    where T: Copy
+{
+    let () = Native::<T>::CHECK;
+    read_torn(src)
+}
+
+/// Performs a volatile write of `value` into the location `dst` points to
+#[inline(always)]
+#[cfg_attr(feature = "timing", track_caller)]
+pub unsafe fn write<T>(dst: *mut T, value: T)
+    where T: Copy
+{
+    let () = Native::<T>::CHECK;
+    write_torn(dst, value)
+}
+
+/// Like `read`, but allows accesses wider than the native access width
+#[inline(always)]
+#[cfg_attr(feature = "timing", track_caller)]
+pub unsafe fn read_torn<T>(src: *const T) -> T
+    where T: Copy
+{
+    cfg_take_once! {
+        #[cfg(feature = "stats")]
+        ::stats::record(src as usize, mem::size_of::<T>(), false);
+    }
+    #[cfg(feature = "timing")]
+    {
+        let start = ::timing::cycles();
+        let value = backend::read(src);
+        ::timing::record(Location::caller(), start, mem::size_of::<T>(), false);
+        value
+    }
+    #[cfg(not(feature = "timing"))]
+    {
+        backend::read(src)
+    }
+}
+
+/// Reads a `T` out of the bytes that `fill` stores into a zeroed buffer of
+/// the size of a `T`
+///
+/// Shared by the backends that get register values as bytes from a
+/// callback.
+///
+/// # Safety
+///
+/// The bytes `fill` leaves in the buffer must be a valid `T`
+#[cfg(any(feature = "indirect", all(feature = "wasm", target_arch = "wasm32")))]
+#[inline(always)]
+pub(crate) unsafe fn read_bytes<T, F>(fill: F) -> T
+    where T: Copy,
+          F: FnOnce(&mut [u8])
+{
+    let mut value = mem::MaybeUninit::<T>::zeroed();
+    fill(::core::slice::from_raw_parts_mut(value.as_mut_ptr() as *mut u8,
+                                           mem::size_of::<T>()));
+    value.assume_init()
+}
+
+/// Like `write`, but allows accesses wider than the native access width
+#[inline(always)]
+#[cfg_attr(feature = "timing", track_caller)]
+pub unsafe fn write_torn<T>(dst: *mut T, value: T)
+    where T: Copy
+{
+    cfg_take_once! {
+        #[cfg(feature = "stats")]
+        ::stats::record(dst as usize, mem::size_of::<T>(), true);
+    }
+    #[cfg(feature = "timing")]
+    {
+        let start = ::timing::cycles();
+        backend::write(dst, value);
+        ::timing::record(Location::caller(), start, mem::size_of::<T>(), true);
+    }
+    #[cfg(not(feature = "timing"))]
+    {
+        backend::write(dst, value)
+    }
+}
+
+/// Copies `src` into the `src.len()` elements starting at `dst`
+///
+/// Each element is written with a volatile access; with the `nightly`
+/// feature, and no feature that hooks accesses, the copy is a single
+/// `volatile_copy_nonoverlapping_memory` that the compiler may perform with
+/// wider accesses.
+#[inline(always)]
+#[cfg_attr(feature = "timing", track_caller)]
+pub unsafe fn copy<T>(dst: *mut T, src: &[T])
+    where T: Copy
+{
+    #[cfg(all(feature = "nightly",
+              not(any(feature = "indirect", feature = "stats", feature = "timing", feature = "wasm"))))]
+    {
+        core::intrinsics::volatile_copy_nonoverlapping_memory(dst, src.as_ptr(), src.len())
+    }
+    #[cfg(not(all(feature = "nightly",
+                  not(any(feature = "indirect", feature = "stats", feature = "timing", feature = "wasm")))))]
+    {
+        for (i, value) in src.iter().enumerate() {
+            write(dst.add(i), *value)
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use poll::Timeout;
+use Read;
+
+/// Tuples of registers that can be read together, see [`read_together`]
+///
+/// Implemented for tuples of up to 6 references to registers.
+pub trait ReadTogether<T>
+    where T: Copy
+{
+    /// Reads every register, in order
+    fn read_all(&self) -> T;
+}
+
+macro_rules! tuples {
+    ($(($($index:tt: $register:ident: $value:ident),+),)+) => {
+        $(
+            impl<'a, $($register, $value),+> ReadTogether<($($value,)+)> for ($(&'a $register,)+)
+                where $($register: Read<$value>, $value: Copy),+
+            {
+                #[inline(always)]
+                fn read_all(&self) -> ($($value,)+) {
+                    ($(self.$index.read(),)+)
+                }
+            }
+        )+
+    }
+}
+
+tuples! {
+    (0: RA: A),
+    (0: RA: A, 1: RB: B),
+    (0: RA: A, 1: RB: B, 2: RC: C),
+    (0: RA: A, 1: RB: B, 2: RC: C, 3: RD: D),
+    (0: RA: A, 1: RB: B, 2: RC: C, 3: RD: D, 4: RE: E),
+    (0: RA: A, 1: RB: B, 2: RC: C, 3: RD: D, 4: RE: E, 5: RF: F),
+}
+
+/// Reads several registers back to back, in order
+///
+/// The reads are issued one after the other with no other code in between,
+/// which keeps the skew between related registers, like a timestamp and
+/// the status it goes with, to a minimum.
+///
+/// ```
+/// use volatile_register::{read_together, RO};
+///
+/// #[repr(C)]
+/// pub struct Rtc {
+///     pub tr: RO<u32>,
+///     pub dr: RO<u16>,
+/// }
+///
+/// let mut memory = [0x0012_3456u32, 0x0025];
+/// let rtc = unsafe { &*(memory.as_mut_ptr() as *const Rtc) };
+///
+/// assert_eq!(read_together((&rtc.tr, &rtc.dr)), (0x0012_3456, 0x25));
+/// ```
+#[inline(always)]
+pub fn read_together<G, T>(registers: G) -> T
+    where G: ReadTogether<T>, T: Copy
+{
+    registers.read_all()
+}
+
+/// Reads several registers back to back, in order, until two consecutive
+/// samples agree
+///
+/// Registers that keep changing, like the halves of a free-running counter,
+/// can't be sampled at once; a sample that two consecutive passes agree on
+/// is one no update landed in the middle of.
+///
+/// At most `max_attempts` passes are made, counted like the reads of
+/// [`Read::wait_until`](trait.Read.html#method.wait_until), so at least 2
+/// are needed to succeed. Returns `Err(Timeout)` with the last sample if no
+/// two consecutive passes agreed; with `max_attempts == 0` no register is
+/// read and there's no sample.
+///
+/// ```
+/// use volatile_register::poll::Timeout;
+/// use volatile_register::{read_together_consistent, RO};
+///
+/// let mut memory = [7u32, 0];
+/// let cnt = unsafe { &*(memory.as_mut_ptr() as *const [RO<u32>; 2]) };
+///
+/// assert_eq!(read_together_consistent((&cnt[0], &cnt[1]), 2), Ok((7, 0)));
+/// assert_eq!(read_together_consistent((&cnt[0], &cnt[1]), 1), Err(Timeout { last: Some((7, 0)) }));
+/// assert_eq!(read_together_consistent((&cnt[0], &cnt[1]), 0), Err(Timeout { last: None }));
+/// ```
+#[inline]
+pub fn read_together_consistent<G, T>(registers: G, max_attempts: usize) -> Result<T, Timeout<T>>
+    where G: ReadTogether<T>, T: Copy + PartialEq
+{
+    let mut last = None;
+    for _ in 0..max_attempts {
+        let sample = registers.read_all();
+        if last == Some(sample) {
+            return Ok(sample);
+        }
+        last = Some(sample);
+    }
+    Err(Timeout { last })
+}
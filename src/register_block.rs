@@ -0,0 +1,1012 @@
+use core::marker::PhantomData;
+use core::mem;
+
+use access::Kind;
+use {raw, Pure, Torn, RO, RW, WO};
+
+/// Memory mapped register block
+///
+/// Implemented by the `struct`s that describe a peripheral's registers to
+/// get raw accessors for registers that are not part of the `struct`, like
+/// undocumented or erratum registers.
+///
+/// Blocks defined with [`register_block!`](macro.register_block.html) also
+/// describe their registers in [`REGISTERS`](#associatedconstant.REGISTERS).
+/// In debug builds the raw accessors check accesses against it, and panic
+/// on reads of write-only registers and writes to read-only registers.
+///
+/// ```
+/// use volatile_register::{RegisterBlock, RW};
+///
+/// #[repr(C)]
+/// pub struct Adc {
+///     pub cr: RW<u32>,
+///     reserved0: [u32; 7],
+///     pub dr: RW<u32>,
+/// }
+///
+/// impl RegisterBlock for Adc {}
+///
+/// let mut memory = [0u32; 9];
+/// memory[0x18 / 4] = 0x0000_0003;
+/// let adc = unsafe { &*(memory.as_mut_ptr() as *const Adc) };
+/// // Erratum 2.3.1: set bit 4 of the undocumented register at offset 0x18
+/// unsafe {
+///     let trim = adc.read_at::<u32>(0x18);
+///     adc.write_at::<u32>(0x18, trim | 1 << 4);
+///     adc.write_at::<u16>(0x20, 0xbeef);
+/// }
+///
+/// unsafe {
+///     assert_eq!(adc.read_at::<u32>(0x18), 0x0000_0013);
+///     assert_eq!(adc.read_at::<u32>(0x20), adc.dr.read());
+///     assert_eq!(adc.read_at::<u16>(0x20), 0xbeef);
+/// }
+/// assert_eq!(adc.cr.read(), 0);
+/// assert_eq!(memory[0x18 / 4], 0x0000_0013);
+/// ```
+pub trait RegisterBlock: Sized {
+    /// The registers of the block, in offset order
+    ///
+    /// Empty unless the block was defined with
+    /// [`register_block!`](macro.register_block.html).
+    const REGISTERS: &'static [RegisterInfo] = &[];
+
+    /// Reads the `T` located `offset` bytes after the start of the block
+    ///
+    /// # Panics
+    ///
+    /// If the access doesn't fit in the block or `offset` is not a multiple
+    /// of the alignment of `T`. In debug builds, also if the access overlaps
+    /// a write-only register.
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because reading a register can have side effects, and
+    /// because the compiler can't verify that `offset` points to a register
+    /// that can be read with a `T` sized access
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    unsafe fn read_at<T>(&self, offset: usize) -> T
+        where T: Copy
+    {
+        #[cfg(debug_assertions)]
+        check::<Self, T>(offset, false);
+        raw::read(at::<Self, T>(self, offset))
+    }
+
+    /// Writes `value` as a `T` located `offset` bytes after the start of the
+    /// block
+    ///
+    /// # Panics
+    ///
+    /// If the access doesn't fit in the block or `offset` is not a multiple
+    /// of the alignment of `T`. In debug builds, also if the access overlaps
+    /// a read-only register.
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful, and because
+    /// the compiler can't verify that `offset` points to a register that can
+    /// be written with a `T` sized access
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    unsafe fn write_at<T>(&self, offset: usize, value: T)
+        where T: Copy
+    {
+        #[cfg(debug_assertions)]
+        check::<Self, T>(offset, true);
+        raw::write(at::<Self, T>(self, offset), value)
+    }
+
+    /// Writes each `(offset, value)` of `table`, in order, with
+    /// [`write_at`](#method.write_at)
+    ///
+    /// For vendor-provided initialization blobs and trim tables.
+    ///
+    /// ```
+    /// use volatile_register::{RegisterBlock, RW};
+    ///
+    /// #[repr(C)]
+    /// pub struct Phy {
+    ///     pub regs: [RW<u32>; 64],
+    /// }
+    ///
+    /// impl RegisterBlock for Phy {}
+    ///
+    /// // from the vendor's reference code: `0x10` is written twice, and
+    /// // the second write wins
+    /// const TRIM: &[(usize, u32)] = &[(0x10, 2), (0x40, 0x0000_1f00), (0x44, 0x0300_0000), (0x10, 1)];
+    ///
+    /// let mut memory = [0u32; 64];
+    /// let phy = unsafe { &*(memory.as_mut_ptr() as *const Phy) };
+    /// unsafe { phy.apply(TRIM) }
+    ///
+    /// assert_eq!(memory[0x10 / 4], 1);
+    /// assert_eq!(memory[0x40 / 4], 0x0000_1f00);
+    /// assert_eq!(memory[0x44 / 4], 0x0300_0000);
+    /// assert_eq!(memory.iter().filter(|&&word| word != 0).count(), 3);
+    /// ```
+    ///
+    /// In debug builds, an entry that writes to a read-only register panics:
+    ///
+    /// ``` should_panic
+    /// #[macro_use]
+    /// extern crate volatile_register;
+    ///
+    /// use volatile_register::{RegisterBlock, RO, RW};
+    ///
+    /// register_block! {
+    ///     pub struct Phy {
+    ///         pub ctrl: RW<u32>,
+    ///         pub id: RO<u32>,
+    ///     }
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut memory = [0u32; 2];
+    ///     let phy = unsafe { &*(memory.as_mut_ptr() as *const Phy) };
+    ///     // `id` is at offset 4
+    ///     unsafe { phy.apply(&[(0x0, 1u32), (0x4, 1)]) }
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Like `write_at`, if one of the writes doesn't fit in the block, is
+    /// misaligned or, in debug builds, overlaps a read-only register. The
+    /// writes that precede it have already been performed.
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful, and because
+    /// the compiler can't verify that the offsets point to registers that can
+    /// be written with a `T` sized access
+    #[inline]
+    #[cfg_attr(feature = "timing", track_caller)]
+    unsafe fn apply<T>(&self, table: &[(usize, T)])
+        where T: Copy
+    {
+        for &(offset, value) in table {
+            self.write_at(offset, value);
+        }
+    }
+
+    /// Waits until the writes previously posted to the block have reached
+    /// the device
+    ///
+    /// Reads back the first [`Pure`](struct.Pure.html) register in
+    /// [`REGISTERS`](#associatedconstant.REGISTERS), so the block should be
+    /// defined with [`register_block!`](macro.register_block.html). Fails,
+    /// without reading anything, if there is no such register.
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate volatile_register;
+    ///
+    /// use volatile_register::{NoPureRegister, Pure, RegisterBlock, RO, RW};
+    ///
+    /// register_block! {
+    ///     pub struct Uart {
+    ///         // reading the data register pops the RX FIFO
+    ///         pub dr: RW<u32>,
+    ///         pub sr: Pure<RO<u32>>,
+    ///     }
+    /// }
+    ///
+    /// register_block! {
+    ///     pub struct Fifo {
+    ///         pub dr: RW<u32>,
+    ///     }
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut memory = [0u32; 2];
+    ///     let uart = unsafe { &*(memory.as_mut_ptr() as *const Uart) };
+    ///     unsafe { uart.dr.write(0x55) }
+    ///     // reads `sr`
+    ///     assert_eq!(uart.flush_posted_writes(), Ok(()));
+    ///
+    ///     let fifo = unsafe { &*(memory.as_mut_ptr() as *const Fifo) };
+    ///     assert_eq!(fifo.flush_posted_writes(), Err(NoPureRegister));
+    /// }
+    /// ```
+    #[inline(always)]
+    fn flush_posted_writes(&self) -> Result<(), NoPureRegister> {
+        self.iter_registers()
+            .find_map(|register| register.peek())
+            .map(|_| ())
+            .ok_or(NoPureRegister)
+    }
+
+    cfg_free! {
+        /// Runs `f` on the block with interrupts disabled
+        ///
+        /// For multi-step hardware sequences that must not be interleaved with
+        /// interrupt handlers touching the same block. Built on
+        /// [`interrupt::free`](interrupt/fn.free.html), and available on the same
+        /// targets.
+        ///
+        /// ``` no_run
+        /// use volatile_register::{RegisterBlock, RW};
+        ///
+        /// #[repr(C)]
+        /// pub struct Flash {
+        ///     pub keyr: RW<u32>,
+        ///     pub cr: RW<u32>,
+        /// }
+        ///
+        /// impl RegisterBlock for Flash {}
+        ///
+        /// let flash = unsafe { &*(0x4002_2004 as *const Flash) };
+        /// // the unlock sequence fails if anything touches the block in between
+        /// flash.with_masked_irq(|flash| unsafe {
+        ///     flash.keyr.write(0x4567_0123);
+        ///     flash.keyr.write(0xcdef_89ab);
+        /// });
+        /// ```
+        ///
+        /// On hosted targets the closures of different threads exclude each
+        /// other, so a read-modify-write inside it isn't lost:
+        ///
+        /// ```
+        /// use std::thread;
+        /// use volatile_register::{RegisterBlock, RW};
+        ///
+        /// #[repr(C)]
+        /// pub struct Counter {
+        ///     pub count: RW<u32>,
+        /// }
+        ///
+        /// impl RegisterBlock for Counter {}
+        ///
+        /// let memory: &'static mut u32 = Box::leak(Box::new(0));
+        /// let address = memory as *mut u32 as usize;
+        ///
+        /// let threads: Vec<_> = (0..4)
+        ///     .map(|_| thread::spawn(move || {
+        ///         let counter = unsafe { &*(address as *const Counter) };
+        ///         for _ in 0..1000 {
+        ///             counter.with_masked_irq(|counter| {
+        ///                 let count = counter.count.read();
+        ///                 thread::yield_now();
+        ///                 unsafe { counter.count.write(count + 1) }
+        ///             });
+        ///         }
+        ///     }))
+        ///     .collect();
+        /// for thread in threads {
+        ///     thread.join().unwrap();
+        /// }
+        ///
+        /// let counter = unsafe { &*(address as *const Counter) };
+        /// assert_eq!(counter.count.read(), 4000);
+        /// ```
+        #[inline(always)]
+        fn with_masked_irq<F, R>(&self, f: F) -> R
+            where F: FnOnce(&Self) -> R
+        {
+            ::interrupt::free(|_| f(self))
+        }
+    }
+
+    /// Iterates over the readable registers of the block, in offset order
+    ///
+    /// Arrays of registers yield one entry per element. Registers whose
+    /// accesses aren't 1, 2, 4 or 8 bytes wide are skipped.
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate volatile_register;
+    ///
+    /// use volatile_register::{RegisterBlock, RO, RW, WO};
+    ///
+    /// register_block! {
+    ///     pub struct Uart {
+    ///         pub dr: WO<u32>,
+    ///         pub sr: RO<u16>,
+    ///         reserved0: u16,
+    ///         pub brr: [RW<u32>; 2],
+    ///     }
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut memory = [0xdead_beef_u32, 0x0000_00c0, 115_200, 9_600];
+    ///     let uart0 = unsafe { &*(memory.as_mut_ptr() as *const Uart) };
+    ///
+    ///     let dump = uart0
+    ///         .iter_registers()
+    ///         .map(|register| {
+    ///             (register.name(), register.index(), register.offset(), unsafe { register.read() })
+    ///         })
+    ///         .collect::<Vec<_>>();
+    ///     assert_eq!(dump, [
+    ///         ("sr", None, 0x4, 0xc0),
+    ///         ("brr", Some(0), 0x8, 115_200),
+    ///         ("brr", Some(1), 0xc, 9_600),
+    ///     ]);
+    /// }
+    /// ```
+    fn iter_registers(&self) -> Registers<'_, Self> {
+        Registers { block: self, register: 0, element: 0 }
+    }
+
+    /// Returns the offset, in bytes, of `register` from the start of the
+    /// block
+    ///
+    /// Together with the `addr` method of the registers, it lets error paths
+    /// report where an access went wrong in the terms of the datasheet.
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate volatile_register;
+    ///
+    /// use volatile_register::{RegisterBlock, RO, RW};
+    ///
+    /// register_block! {
+    ///     pub struct Rcc {
+    ///         pub cr: RW<u32>,
+    ///         pub sr: RO<u32>,
+    ///     }
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut memory = [0u32; 2];
+    ///     let rcc = unsafe { &*(memory.as_mut_ptr() as *const Rcc) };
+    ///
+    ///     assert_eq!(rcc.offset_of(&rcc.sr), 0x4);
+    ///     assert_eq!(rcc.sr.addr(), memory.as_ptr() as usize + 0x4);
+    /// }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// If `register` doesn't belong to the block
+    fn offset_of<R>(&self, register: &R) -> usize {
+        let offset = (register as *const R as usize).wrapping_sub(self as *const Self as usize);
+        assert!(offset < mem::size_of::<Self>(), "register out of the register block");
+        offset
+    }
+
+    /// Computes the CRC-32 of the values of the [`Pure`](struct.Pure.html)
+    /// registers of the block
+    ///
+    /// The other registers are skipped, so computing the checksum never
+    /// changes the state of the peripheral. Configuration registers that
+    /// must not change once set up can be checksummed after initialization
+    /// and periodically checked against it with
+    /// [`verify_crc32`](#method.verify_crc32), to detect silent corruption.
+    ///
+    /// The CRC is the IEEE 802.3 one, computed over the values in offset
+    /// order, each one of the width of its register and least significant
+    /// byte first.
+    ///
+    /// ```
+    /// #[macro_use]
+    /// extern crate volatile_register;
+    ///
+    /// use volatile_register::{Corrupted, Pure, RegisterBlock, RW};
+    ///
+    /// register_block! {
+    ///     pub struct Clock {
+    ///         pub cfgr: Pure<RW<u32>>,
+    ///         pub pllcfgr: Pure<RW<u32>>,
+    ///     }
+    /// }
+    ///
+    /// fn main() {
+    ///     let mut memory = [0u32; 2];
+    ///     let clock = unsafe { &*(memory.as_mut_ptr() as *const Clock) };
+    ///
+    ///     unsafe { clock.pllcfgr.write(0x2400_3010) }
+    ///     let crc = clock.crc32();
+    ///     assert_eq!(clock.verify_crc32(crc), Ok(()));
+    ///
+    ///     // a bit flips
+    ///     unsafe { clock.cfgr.write(1 << 7) }
+    ///     assert_eq!(clock.verify_crc32(crc), Err(Corrupted { expected: crc, actual: clock.crc32() }));
+    /// }
+    /// ```
+    fn crc32(&self) -> u32 {
+        let mut crc = !0;
+        for register in self.iter_registers() {
+            if let Some(value) = register.peek() {
+                for byte in &value.to_le_bytes()[..register.info().width] {
+                    crc = crc32_update(crc, *byte);
+                }
+            }
+        }
+        !crc
+    }
+
+    /// Checks that [`crc32`](#method.crc32) still returns `expected`
+    fn verify_crc32(&self, expected: u32) -> Result<(), Corrupted> {
+        let actual = self.crc32();
+        if actual == expected {
+            Ok(())
+        } else {
+            Err(Corrupted { expected, actual })
+        }
+    }
+}
+
+/// Error returned by [`RegisterBlock::flush_posted_writes`] when the block
+/// has no `Pure` register to read back
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NoPureRegister;
+
+/// Error returned by [`RegisterBlock::verify_crc32`] when the registers no
+/// longer hold the checksummed values
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Corrupted {
+    /// The checksum computed earlier
+    pub expected: u32,
+    /// The checksum of the current values
+    pub actual: u32,
+}
+
+/// Feeds `byte` to the reflected CRC-32 (polynomial `0x04c11db7`)
+fn crc32_update(mut crc: u32, byte: u8) -> u32 {
+    crc ^= u32::from(byte);
+    for _ in 0..8 {
+        crc = (crc >> 1) ^ (0xedb8_8320 & (crc & 1).wrapping_neg());
+    }
+    crc
+}
+
+/// Returns a pointer to the `T` located `offset` bytes after the start of
+/// `block`, checking that the access fits in the block and is aligned
+#[inline(always)]
+fn at<B, T>(block: &B, offset: usize) -> *mut T {
+    assert!(offset <= mem::size_of::<B>()
+                && mem::size_of::<T>() <= mem::size_of::<B>() - offset,
+            "access out of the register block");
+    assert!(offset & (mem::align_of::<T>() - 1) == 0, "misaligned register access");
+    (block as *const B as *mut u8).wrapping_add(offset) as *mut T
+}
+
+/// Checks a `T` sized access at `offset` against the registers of `B`
+#[cfg(debug_assertions)]
+fn check<B, T>(offset: usize, write: bool)
+    where B: RegisterBlock
+{
+    let end = offset.saturating_add(mem::size_of::<T>());
+    for register in B::REGISTERS {
+        if offset >= register.offset + register.size || end <= register.offset {
+            continue;
+        }
+        if let Some(access) = register.access {
+            if write {
+                assert!(access.is_writable(),
+                        "write to read-only register `{}`", register.name);
+            } else {
+                assert!(access.is_readable(),
+                        "read of write-only register `{}`", register.name);
+            }
+        }
+    }
+}
+
+/// Description of a field of a register block
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RegisterInfo {
+    /// Name of the field
+    pub name: &'static str,
+    /// Offset, in bytes, from the start of the block
+    pub offset: usize,
+    /// Size in bytes
+    pub size: usize,
+    /// Size of a single access, in bytes; smaller than `size` for arrays of
+    /// registers
+    pub width: usize,
+    /// Allowed accesses, or `None` for reserved space
+    pub access: Option<Kind>,
+    /// Value after reset, if documented
+    pub reset: Option<u64>,
+    /// `true` if reads have no side effects, see [`Pure`](struct.Pure.html)
+    pub pure: bool,
+    /// `true` if the register is accessed as two half-width accesses, low
+    /// half first, see [`SplitAccess`](struct.SplitAccess.html)
+    pub split: bool,
+}
+
+/// Iterator over the readable registers of a block
+///
+/// Returned by [`RegisterBlock::iter_registers`](trait.RegisterBlock.html#method.iter_registers)
+pub struct Registers<'a, B> {
+    block: &'a B,
+    register: usize,
+    element: usize,
+}
+
+impl<'a, B> Iterator for Registers<'a, B>
+    where B: RegisterBlock
+{
+    type Item = RegisterEntry<'a>;
+
+    fn next(&mut self) -> Option<RegisterEntry<'a>> {
+        while let Some(info) = B::REGISTERS.get(self.register) {
+            let readable = info.access.is_some_and(Kind::is_readable)
+                && matches!(info.width, 1 | 2 | 4 | 8);
+            if !readable || self.element >= info.size / info.width {
+                self.register += 1;
+                self.element = 0;
+                continue;
+            }
+
+            let element = self.element;
+            self.element += 1;
+            return Some(RegisterEntry {
+                info,
+                index: if info.size == info.width { None } else { Some(element) },
+                address: self.block as *const B as usize,
+                _marker: PhantomData,
+            });
+        }
+        None
+    }
+}
+
+/// Readable register of a block, or element of an array of registers
+pub struct RegisterEntry<'a> {
+    info: &'static RegisterInfo,
+    index: Option<usize>,
+    address: usize,
+    _marker: PhantomData<&'a ()>,
+}
+
+impl<'a> RegisterEntry<'a> {
+    /// Returns the description of the field the register belongs to
+    pub fn info(&self) -> &'static RegisterInfo {
+        self.info
+    }
+
+    /// Returns the name of the field the register belongs to
+    pub fn name(&self) -> &'static str {
+        self.info.name
+    }
+
+    /// Returns the position of the register in its array, or `None` if the
+    /// field is not an array
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    /// Returns the offset, in bytes, of the register from the start of the
+    /// block
+    pub fn offset(&self) -> usize {
+        self.info.offset + self.index.unwrap_or(0) * self.info.width
+    }
+
+    /// Returns the allowed accesses
+    pub fn access(&self) -> Kind {
+        // only registers are yielded by `Registers`
+        self.info.access.unwrap_or(Kind::ReadOnly)
+    }
+
+    /// Reads the register, zero-extended to 64 bits
+    ///
+    /// 8 byte registers are read with two accesses on targets that can't
+    /// read them at once, like [`Torn`](struct.Torn.html) registers.
+    /// [`SplitAccess`](struct.SplitAccess.html) registers are read low half
+    /// first.
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because reading a register can have side effects
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn read(&self) -> u64 {
+        let address = self.address + self.offset();
+        if self.info.split {
+            return match self.info.width {
+                2 => read_split::<u8>(address),
+                4 => read_split::<u16>(address),
+                _ => read_split::<u32>(address),
+            };
+        }
+        match self.info.width {
+            1 => u64::from(raw::read(address as *mut u8)),
+            2 => u64::from(raw::read(address as *mut u16)),
+            4 => u64::from(raw::read(address as *mut u32)),
+            _ => raw::read_torn(address as *mut u64),
+        }
+    }
+
+    /// Reads the register like [`read`](#method.read), if its reads have no
+    /// side effects
+    ///
+    /// Returns `None`, without accessing the register, if it's not
+    /// [`Pure`](struct.Pure.html).
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub fn peek(&self) -> Option<u64> {
+        if self.info.pure {
+            Some(unsafe { self.read() })
+        } else {
+            None
+        }
+    }
+}
+
+/// Reads the two `H` halves of a register, low half first
+///
+/// The halves of 8 byte registers are torn on 16-bit targets.
+#[inline(always)]
+#[cfg_attr(feature = "timing", track_caller)]
+unsafe fn read_split<H>(address: usize) -> u64
+    where H: Copy + Into<u64>
+{
+    let half = mem::size_of::<H>();
+    let (low, high) = if cfg!(target_endian = "little") {
+        (address, address + half)
+    } else {
+        (address + half, address)
+    };
+    let low = raw::read_torn(low as *mut H).into();
+    let high = raw::read_torn(high as *mut H).into();
+    (high << (8 * half)) | low
+}
+
+/// Registers that can be returned to their reset value
+///
+/// Used by the `reset` and `is_reset` methods generated by
+/// [`register_block!`](macro.register_block.html). Arrays of registers use
+/// the same reset value for every element.
+pub trait Resettable<T> {
+    /// Writes `value` into the register; does nothing for read-only
+    /// registers
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    unsafe fn reset_to(&self, value: T);
+
+    /// Returns `true` if the register holds `value`; always `true` for
+    /// write-only registers, which can't be checked
+    fn is_at(&self, value: T) -> bool;
+}
+
+impl<T> Resettable<T> for RO<T>
+    where T: Copy + PartialEq
+{
+    unsafe fn reset_to(&self, _: T) {}
+
+    fn is_at(&self, value: T) -> bool {
+        self.read() == value
+    }
+}
+
+impl<T> Resettable<T> for RW<T>
+    where T: Copy + PartialEq
+{
+    unsafe fn reset_to(&self, value: T) {
+        self.write(value)
+    }
+
+    fn is_at(&self, value: T) -> bool {
+        self.read() == value
+    }
+}
+
+impl<T> Resettable<T> for WO<T>
+    where T: Copy
+{
+    unsafe fn reset_to(&self, value: T) {
+        self.write(value)
+    }
+
+    fn is_at(&self, _: T) -> bool {
+        true
+    }
+}
+
+impl<T> Resettable<T> for Torn<RO<T>>
+    where T: Copy + PartialEq
+{
+    unsafe fn reset_to(&self, _: T) {}
+
+    fn is_at(&self, value: T) -> bool {
+        self.read() == value
+    }
+}
+
+impl<T> Resettable<T> for Torn<RW<T>>
+    where T: Copy + PartialEq
+{
+    unsafe fn reset_to(&self, value: T) {
+        self.write(value)
+    }
+
+    fn is_at(&self, value: T) -> bool {
+        self.read() == value
+    }
+}
+
+impl<T> Resettable<T> for Torn<WO<T>>
+    where T: Copy
+{
+    unsafe fn reset_to(&self, value: T) {
+        self.write(value)
+    }
+
+    fn is_at(&self, _: T) -> bool {
+        true
+    }
+}
+
+impl<R, T> Resettable<T> for Pure<R>
+    where R: Resettable<T>
+{
+    unsafe fn reset_to(&self, value: T) {
+        (**self).reset_to(value)
+    }
+
+    fn is_at(&self, value: T) -> bool {
+        (**self).is_at(value)
+    }
+}
+
+impl<R, T, const N: usize> Resettable<T> for [R; N]
+    where R: Resettable<T>, T: Copy
+{
+    unsafe fn reset_to(&self, value: T) {
+        for register in self {
+            register.reset_to(value)
+        }
+    }
+
+    fn is_at(&self, value: T) -> bool {
+        self.iter().all(|register| register.is_at(value))
+    }
+}
+
+/// Defines a `#[repr(C)]` register block and implements
+/// [`RegisterBlock`](trait.RegisterBlock.html) for it, describing its fields
+/// in `REGISTERS`
+///
+/// Fields are registers (`RO`, `RW`, `WO`, ..) or, for reserved space, plain
+/// integers; arrays of either are allowed too. See
+/// [`access::HasAccess`](access/trait.HasAccess.html).
+///
+/// Registers can be given a reset value, `= value` after the type. The block
+/// then gets a `reset` method, which writes the reset values, and an
+/// `is_reset` method, which checks that the readable registers hold them. See
+/// [`Resettable`](trait.Resettable.html). Reset values must be integers; they
+/// are also recorded in `REGISTERS` and in the block's `DESCRIPTION`, see the
+/// [`describe`](describe/index.html) module.
+///
+/// Fields can be prefixed with their offset from the datasheet, `0x04 =>`.
+/// The offset is checked at compile time, so a field that was copy-pasted
+/// at the offset of another one, or a missing reserved gap, is caught
+/// before the code runs on hardware:
+///
+/// ``` compile_fail
+/// #[macro_use]
+/// extern crate volatile_register;
+///
+/// use volatile_register::{RO, RW};
+///
+/// register_block! {
+///     pub struct Spi {
+///         0x00 => pub cr1: RW<u32>,
+///         0x04 => pub cr2: RW<u32>,
+///         // copy-paste error: `sr` is at 0x08
+///         0x04 => pub sr: RO<u32>,
+///     }
+/// }
+/// # fn main() {}
+/// ```
+///
+/// With the `emulation` feature, `zeroed` creates a block in RAM whose
+/// registers all read as zero, for tests and emulators:
+///
+/// ```
+/// #[macro_use]
+/// extern crate volatile_register;
+///
+/// # #[cfg(feature = "emulation")]
+/// # fn main() {
+/// use volatile_register::{Pure, RegisterBlock, RO, RW};
+///
+/// register_block! {
+///     pub struct Adc {
+///         pub cr: RW<u32> = 0x0000_0001,
+///         pub sr: Pure<RO<u16>>,
+///         reserved0: [u16; 1],
+///         pub data: [RO<u32>; 4],
+///     }
+/// }
+///
+/// let adc = Adc::zeroed();
+///
+/// assert_eq!(adc.cr.read(), 0);
+/// assert_eq!(adc.sr.read(), 0);
+/// assert!(adc.data.iter().all(|data| data.read() == 0));
+/// for register in adc.iter_registers() {
+///     assert_eq!(unsafe { register.read() }, 0, "{}", register.name());
+/// }
+/// assert!(!adc.is_reset());
+/// # }
+/// # #[cfg(not(feature = "emulation"))]
+/// # fn main() {}
+/// ```
+///
+/// Fields of a block never overlap. Registers that share addresses, like
+/// the alternate layouts of a multi-function peripheral, are described by
+/// separate blocks; see [`Overlay`](struct.Overlay.html).
+///
+/// ```
+/// #[macro_use]
+/// extern crate volatile_register;
+///
+/// use volatile_register::{RO, RW};
+///
+/// register_block! {
+///     pub struct Uart {
+///         0x00 => pub cr: RW<u32> = 0x0000_0300,
+///         0x04 => pub sr: RO<u32> = 0x0000_00c0,
+///         0x08 => pub dr: RW<u32>,
+///     }
+/// }
+///
+/// fn main() {
+///     let mut memory = [0u32, 0xc0, 0];
+///     let uart = unsafe { &*(memory.as_mut_ptr() as *const Uart) };
+///
+///     assert!(!uart.is_reset());
+///     unsafe { uart.reset() }
+///     assert!(uart.is_reset());
+///     assert_eq!(uart.cr.read(), 0x300);
+/// }
+/// ```
+///
+/// ``` should_panic
+/// #[macro_use]
+/// extern crate volatile_register;
+///
+/// use volatile_register::{RegisterBlock, RO, RW};
+///
+/// register_block! {
+///     /// Timer
+///     pub struct Tim {
+///         /// Control
+///         pub cr: RW<u32>,
+///         reserved0: [u32; 2],
+///         /// Counter
+///         pub cnt: RO<u32>,
+///     }
+/// }
+///
+/// fn main() {
+///     assert_eq!(Tim::REGISTERS[3].name, "cnt");
+///     assert_eq!(Tim::REGISTERS[3].offset, 0xc);
+///
+///     let mut memory = [0u32; 4];
+///     let tim = unsafe { &*(memory.as_mut_ptr() as *const Tim) };
+///     unsafe {
+///         // fine: reserved space
+///         tim.write_at::<u32>(0x4, 1);
+///         // panics in debug builds: `cnt` is read-only
+///         tim.write_at::<u32>(0xc, 1);
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! register_block {
+    ($(#[$attr:meta])*
+     $vis:vis struct $name:ident {
+         $(
+             $(#[$field_attr:meta])*
+             $($offset:literal =>)?
+             $field_vis:vis $field:ident: $ty:ty $(= $reset:expr)?
+         ),* $(,)?
+     }) => {
+        $(#[$attr])*
+        #[repr(C)]
+        $vis struct $name {
+            $(
+                $(#[$field_attr])*
+                $(#[doc = concat!("\n\nReset value: `", stringify!($reset), "`")])?
+                $field_vis $field: $ty,
+            )*
+        }
+
+        $($(
+            const _: () = assert!(
+                $crate::__offset_of!($name, $field) == $offset,
+                concat!("`", stringify!($field), "` is not at offset ", stringify!($offset)),
+            );
+        )?)*
+
+        impl $name {
+            /// Writes the reset value of every register that has one
+            ///
+            /// # Safety
+            ///
+            /// `unsafe` because writes to a register are side effectful
+            #[allow(dead_code)]
+            pub unsafe fn reset(&self) {
+                $($(
+                    $crate::Resettable::reset_to(&self.$field, $reset);
+                )?)*
+            }
+
+            /// Returns `true` if every readable register that has a reset
+            /// value holds it
+            #[allow(dead_code)]
+            pub fn is_reset(&self) -> bool {
+                true $($(
+                    && $crate::Resettable::is_at(&self.$field, $reset)
+                )?)*
+            }
+        }
+
+        impl $crate::RegisterBlock for $name {
+            const REGISTERS: &'static [$crate::RegisterInfo] = &[
+                $(
+                    $crate::RegisterInfo {
+                        name: stringify!($field),
+                        offset: $crate::__offset_of!($name, $field),
+                        size: $crate::__size_of::<$ty>(),
+                        width: <$ty as $crate::access::HasAccess>::WIDTH,
+                        access: <$ty as $crate::access::HasAccess>::ACCESS,
+                        reset: $crate::__reset!($($reset)?),
+                        pure: <$ty as $crate::access::HasAccess>::PURE,
+                        split: <$ty as $crate::access::HasAccess>::SPLIT,
+                    },
+                )*
+            ];
+        }
+
+        impl $name {
+            /// Machine-readable description of the register map
+            ///
+            /// See the `volatile_register::describe` module for the format.
+            #[allow(dead_code)]
+            pub const DESCRIPTION: [u8; $crate::describe::len(
+                stringify!($name),
+                <$name as $crate::RegisterBlock>::REGISTERS,
+            )] = $crate::describe::encode(
+                stringify!($name),
+                <$name as $crate::RegisterBlock>::REGISTERS,
+            );
+        }
+
+        $crate::__zeroed!($name { $($field: $ty),* });
+
+        $crate::__export_map!($name);
+    }
+}
+
+/// Defines `zeroed` for a block declared with `register_block!`
+#[cfg(feature = "emulation")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __zeroed {
+    ($name:ident { $($field:ident: $ty:ty),* }) => {
+        impl $name {
+            /// Returns a block, in RAM, whose fields hold their `Default`
+            /// value: zero for the register types and the integer arrays
+            ///
+            /// Only callable when every field implements `Default`.
+            #[allow(dead_code)]
+            pub fn zeroed() -> Self
+                where $(for<'a> $ty: Default),*
+            {
+                $name {
+                    $(
+                        $field: Default::default(),
+                    )*
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(feature = "emulation"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __zeroed {
+    ($name:ident { $($field:ident: $ty:ty),* }) => {}
+}
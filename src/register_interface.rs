@@ -0,0 +1,133 @@
+//! `device-driver` register interface
+//!
+//! With the `device-driver` feature enabled, [`Mmio`] implements the
+//! `RegisterInterface` trait of the `device-driver` crate over a memory
+//! mapped [`RegisterBlock`](../trait.RegisterBlock.html), so that drivers
+//! generated by that toolkit access the block through this crate, like the
+//! rest of the firmware, and with the same hooks (`stats`, `timing`, ..).
+//!
+//! Addresses are byte offsets from the start of the block. Each register is
+//! accessed with a single access of its size, 8, 16, 32 or, on 64-bit
+//! targets, 64 bits; other sizes are rejected with [`UnsupportedSize`]. The
+//! bytes are exchanged in memory order, so describe the registers with the
+//! byte order of the target.
+//!
+//! ```
+//! extern crate device_driver;
+//! extern crate volatile_register;
+//!
+//! use device_driver::RegisterInterface;
+//! use volatile_register::register_interface::{Mmio, UnsupportedSize};
+//! use volatile_register::{RegisterBlock, RW};
+//!
+//! #[repr(C)]
+//! pub struct Gpio {
+//!     pub moder: RW<u32>,
+//!     pub odr: RW<u16>,
+//! }
+//!
+//! impl RegisterBlock for Gpio {}
+//!
+//! fn main() {
+//!     let mut memory = [0u32; 2];
+//!     let gpio = unsafe { &*(memory.as_mut_ptr() as *const Gpio) };
+//!     let mut interface = unsafe { Mmio::new(gpio) };
+//!
+//!     interface.write_register(4, 16, &0x0020_u16.to_ne_bytes()).unwrap();
+//!     assert_eq!(gpio.odr.read(), 0x0020);
+//!
+//!     unsafe { gpio.moder.write(0x2800_0000) }
+//!     let mut moder = [0; 4];
+//!     interface.read_register(0, 32, &mut moder).unwrap();
+//!     assert_eq!(u32::from_ne_bytes(moder), 0x2800_0000);
+//!
+//!     assert_eq!(interface.read_register(0, 24, &mut [0; 3]), Err(UnsupportedSize { size_bits: 24 }));
+//! }
+//! ```
+
+use device_driver::RegisterInterface;
+
+use RegisterBlock;
+
+/// `device-driver` register interface to the register block `B`
+pub struct Mmio<'a, B>
+    where B: 'a
+{
+    block: &'a B,
+}
+
+impl<'a, B> Mmio<'a, B>
+    where B: RegisterBlock
+{
+    /// Creates the interface to `block`
+    ///
+    /// # Safety
+    ///
+    /// The addresses and sizes the driver accesses through the interface
+    /// must be registers of `block` that can be accessed with an access of
+    /// that size, see [`RegisterBlock::read_at`](../trait.RegisterBlock.html#method.read_at)
+    pub unsafe fn new(block: &'a B) -> Self {
+        Mmio { block }
+    }
+}
+
+/// Error returned for registers whose size isn't supported by [`Mmio`], or
+/// doesn't match the size of the data
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnsupportedSize {
+    /// Size of the register, in bits
+    pub size_bits: u32,
+}
+
+/// Copies `data`, `N` bytes long, into an array
+#[inline(always)]
+fn bytes<const N: usize>(data: &[u8]) -> [u8; N] {
+    let mut bytes = [0; N];
+    bytes.copy_from_slice(data);
+    bytes
+}
+
+impl<'a, B> RegisterInterface for Mmio<'a, B>
+    where B: RegisterBlock
+{
+    type Error = UnsupportedSize;
+    type AddressType = usize;
+
+    fn write_register(&mut self, address: usize, size_bits: u32, data: &[u8])
+                      -> Result<(), UnsupportedSize>
+    {
+        if data.len() * 8 != size_bits as usize {
+            return Err(UnsupportedSize { size_bits });
+        }
+        unsafe {
+            match size_bits {
+                8 => self.block.write_at(address, data[0]),
+                16 => self.block.write_at(address, u16::from_ne_bytes(bytes(data))),
+                32 => self.block.write_at(address, u32::from_ne_bytes(bytes(data))),
+                #[cfg(target_pointer_width = "64")]
+                64 => self.block.write_at(address, u64::from_ne_bytes(bytes(data))),
+                _ => return Err(UnsupportedSize { size_bits }),
+            }
+        }
+        Ok(())
+    }
+
+    fn read_register(&mut self, address: usize, size_bits: u32, data: &mut [u8])
+                     -> Result<(), UnsupportedSize>
+    {
+        if data.len() * 8 != size_bits as usize {
+            return Err(UnsupportedSize { size_bits });
+        }
+        unsafe {
+            match size_bits {
+                8 => data[0] = self.block.read_at::<u8>(address),
+                16 => data.copy_from_slice(&self.block.read_at::<u16>(address).to_ne_bytes()),
+                32 => data.copy_from_slice(&self.block.read_at::<u32>(address).to_ne_bytes()),
+                #[cfg(target_pointer_width = "64")]
+                64 => data.copy_from_slice(&self.block.read_at::<u64>(address).to_ne_bytes()),
+                _ => return Err(UnsupportedSize { size_bits }),
+            }
+        }
+        Ok(())
+    }
+}
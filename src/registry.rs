@@ -0,0 +1,55 @@
+//! Tables installed once at runtime
+
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+use TakeOnce;
+
+/// Error returned when installing a table after one was already installed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AlreadyInstalled;
+
+/// `'static` table that can be installed once, and read from anywhere
+/// afterwards
+pub(crate) struct Registry<T: 'static> {
+    installing: TakeOnce,
+    installed: AtomicBool,
+    table: UnsafeCell<&'static [T]>,
+}
+
+// the table is written once, by the caller that took `installing`, before
+// `installed` is set; it's only read after `installed` is seen set
+unsafe impl<T> Sync for Registry<T>
+    where T: Sync
+{}
+
+impl<T> Registry<T> {
+    /// Creates a registry without a table
+    pub(crate) const fn new() -> Self {
+        Registry {
+            installing: TakeOnce::new(),
+            installed: AtomicBool::new(false),
+            table: UnsafeCell::new(&[]),
+        }
+    }
+
+    /// Installs `table`, unless a table was installed before
+    pub(crate) fn install(&self, table: &'static [T]) -> Result<(), AlreadyInstalled> {
+        if !self.installing.take() {
+            return Err(AlreadyInstalled);
+        }
+        unsafe { *self.table.get() = table }
+        self.installed.store(true, Ordering::Release);
+        Ok(())
+    }
+
+    /// Returns the installed table; an empty one if none was installed yet
+    #[inline(always)]
+    pub(crate) fn get(&self) -> &'static [T] {
+        if self.installed.load(Ordering::Acquire) {
+            unsafe { *self.table.get() }
+        } else {
+            &[]
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use access::{HasAccess, Kind};
+use {Int, Read, Write, RO, RW, WO};
+
+macro_rules! rev {
+    ($(#[$attr:meta])* $name:ident, $transform:ident) => {
+        $(#[$attr])*
+        #[repr(transparent)]
+        pub struct $name<R> {
+            register: R,
+        }
+
+        impl<R> HasAccess for $name<R>
+            where R: HasAccess
+        {
+            const ACCESS: Option<Kind> = R::ACCESS;
+            const WIDTH: usize = R::WIDTH;
+            const PURE: bool = R::PURE;
+            const SPLIT: bool = R::SPLIT;
+        }
+
+        #[cfg(feature = "emulation")]
+        impl<R> Default for $name<R>
+            where R: Default
+        {
+            fn default() -> Self {
+                $name { register: R::default() }
+            }
+        }
+
+        impl<R> $name<R> {
+            /// Returns the address of the register
+            #[inline(always)]
+            pub fn addr(&self) -> usize {
+                self as *const $name<R> as usize
+            }
+        }
+
+        impl<T> $name<RO<T>>
+            where T: Int
+        {
+            /// Reads the value of the register, in logical order
+            #[inline(always)]
+            #[cfg_attr(feature = "timing", track_caller)]
+            pub fn read(&self) -> T {
+                self.register.read().$transform()
+            }
+        }
+
+        impl<T> $name<RW<T>>
+            where T: Int
+        {
+            /// Performs a read-modify-write operation, in logical order
+            ///
+            /// # Safety
+            ///
+            /// `unsafe` because writes to a register are side effectful
+            #[inline(always)]
+            #[cfg_attr(feature = "timing", track_caller)]
+            pub unsafe fn modify<F>(&self, f: F)
+                where F: FnOnce(T) -> T
+            {
+                self.register.modify(|value| f(value.$transform()).$transform())
+            }
+
+            /// Reads the value of the register, in logical order
+            #[inline(always)]
+            #[cfg_attr(feature = "timing", track_caller)]
+            pub fn read(&self) -> T {
+                self.register.read().$transform()
+            }
+
+            /// Writes a `value`, in logical order, into the register
+            ///
+            /// # Safety
+            ///
+            /// `unsafe` because writes to a register are side effectful
+            #[inline(always)]
+            #[cfg_attr(feature = "timing", track_caller)]
+            pub unsafe fn write(&self, value: T) {
+                self.register.write(value.$transform())
+            }
+        }
+
+        impl<T> $name<WO<T>>
+            where T: Int
+        {
+            /// Writes a `value`, in logical order, into the register
+            ///
+            /// # Safety
+            ///
+            /// `unsafe` because writes to a register are side effectful
+            #[inline(always)]
+            #[cfg_attr(feature = "timing", track_caller)]
+            pub unsafe fn write(&self, value: T) {
+                self.register.write(value.$transform())
+            }
+        }
+
+        impl<T> Read<T> for $name<RO<T>>
+            where T: Int
+        {
+            #[inline(always)]
+            #[cfg_attr(feature = "timing", track_caller)]
+            fn read(&self) -> T {
+                $name::<RO<T>>::read(self)
+            }
+        }
+
+        impl<T> Read<T> for $name<RW<T>>
+            where T: Int
+        {
+            #[inline(always)]
+            #[cfg_attr(feature = "timing", track_caller)]
+            fn read(&self) -> T {
+                $name::<RW<T>>::read(self)
+            }
+        }
+
+        impl<T> Write<T> for $name<RW<T>>
+            where T: Int
+        {
+            #[inline(always)]
+            #[cfg_attr(feature = "timing", track_caller)]
+            unsafe fn write(&self, value: T) {
+                $name::<RW<T>>::write(self, value)
+            }
+        }
+
+        impl<T> Write<T> for $name<WO<T>>
+            where T: Int
+        {
+            #[inline(always)]
+            #[cfg_attr(feature = "timing", track_caller)]
+            unsafe fn write(&self, value: T) {
+                $name::<WO<T>>::write(self, value)
+            }
+        }
+    }
+}
+
+rev! {
+    /// Register whose bits are in the reverse order of its logical layout
+    ///
+    /// Bit `n` of the value read or written is bit `BITS - 1 - n` of the
+    /// register, like the data registers of CRC units configured with
+    /// `REV_IN` / `REV_OUT`.
+    ///
+    /// ```
+    /// use volatile_register::{BitRev, RW};
+    ///
+    /// let mut memory = 0x0000_0001u32;
+    /// let dr = unsafe { &*(&mut memory as *mut u32 as *const BitRev<RW<u32>>) };
+    ///
+    /// assert_eq!(dr.read(), 0x8000_0000);
+    /// unsafe { dr.write(0x0000_0003) }
+    /// assert_eq!(memory, 0xc000_0000);
+    /// ```
+    BitRev, rev_bits
+}
+
+rev! {
+    /// Register whose bytes are in the reverse order of its logical layout
+    ///
+    /// Byte `n` of the value read or written is byte `size - 1 - n` of the
+    /// register, as in blocks designed for a bus of the opposite endianness.
+    ///
+    /// ```
+    /// use volatile_register::{ByteRev, RW};
+    ///
+    /// let mut memory = 0x1234_5678u32;
+    /// let dr = unsafe { &*(&mut memory as *mut u32 as *const ByteRev<RW<u32>>) };
+    ///
+    /// assert_eq!(dr.read(), 0x7856_3412);
+    /// ```
+    ByteRev, rev_bytes
+}
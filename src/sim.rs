@@ -0,0 +1,325 @@
+//! Register map simulation
+//!
+//! The `sim` feature provides an [`indirect`](../indirect/index.html) backend
+//! for host-side tests: register blocks are created over a RAM buffer and
+//! every access is checked against a description of the register map taken
+//! from the datasheet. Reads of write-only registers, writes to read-only
+//! registers and accesses to offsets that are not part of any register are
+//! reported to a handler, so that they fail tests instead of being silently
+//! ignored or misbehaving on hardware.
+//!
+//! When the handler returns, the offending access behaves like it would on a
+//! typical bus: reads return zero and writes are dropped.
+//!
+//! The simulation is installed for the calling thread only, so tests running
+//! in parallel each check their accesses against their own register map.
+//! Accesses made by other threads go straight to memory.
+//!
+//! [`Watch`]es attach callbacks to individual registers, to script the values
+//! the driver reads or to check the values, and order, of its writes.
+//!
+//! ```
+//! use volatile_register::{RO, RW};
+//! use volatile_register::access::Kind;
+//! use volatile_register::sim::{self, Config, Region, Violation};
+//!
+//! #[repr(C)]
+//! pub struct Uart {
+//!     pub dr: RW<u32>,
+//!     // BUG: the datasheet says this register is read-only
+//!     pub sr: RW<u32>,
+//! }
+//!
+//! fn on_violation(violation: &Violation) {
+//!     assert_eq!(*violation, Violation::WriteToReadOnly { offset: 4, size: 4 });
+//! }
+//!
+//! static CONFIG: Config = Config {
+//!     regions: &[
+//!         Region { offset: 0, size: 4, access: Kind::ReadWrite },
+//!         Region { offset: 4, size: 4, access: Kind::ReadOnly },
+//!     ],
+//!     watches: &[],
+//!     on_violation,
+//! };
+//!
+//! let mut memory = [0u32; 2];
+//! let uart = unsafe { &*(memory.as_mut_ptr() as *const Uart) };
+//!
+//! unsafe { sim::install(uart as *const Uart as usize, &CONFIG) }
+//! unsafe { uart.sr.write(1) }
+//! assert_eq!(uart.sr.read(), 0);
+//! ```
+//!
+//! Accesses below the base address, like a block placed at the wrong
+//! address, don't follow the register map either:
+//!
+//! ```
+//! use std::sync::atomic::{AtomicBool, Ordering};
+//!
+//! use volatile_register::RW;
+//! use volatile_register::access::Kind;
+//! use volatile_register::sim::{self, Config, Region, Violation};
+//!
+//! static REPORTED: AtomicBool = AtomicBool::new(false);
+//!
+//! fn on_violation(violation: &Violation) {
+//!     assert_eq!(*violation, Violation::Reserved { offset: 0usize.wrapping_sub(2), size: 2, write: false });
+//!     REPORTED.store(true, Ordering::Relaxed);
+//! }
+//!
+//! static CONFIG: Config = Config {
+//!     regions: &[Region { offset: 0, size: 4, access: Kind::ReadWrite }],
+//!     watches: &[],
+//!     on_violation,
+//! };
+//!
+//! let mut memory = [0u32; 2];
+//! let base = memory.as_mut_ptr() as usize + 4;
+//! let misplaced = unsafe { &*((base - 2) as *const RW<u16>) };
+//!
+//! unsafe { sim::install(base, &CONFIG) }
+//! assert_eq!(misplaced.read(), 0);
+//! assert!(REPORTED.load(Ordering::Relaxed));
+//! ```
+//!
+//! Checking that the driver enables the peripheral before writing the baud
+//! rate, and that it polls the status register until the peripheral is ready:
+//!
+//! ```
+//! use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+//!
+//! use volatile_register::{RO, RW};
+//! use volatile_register::access::Kind;
+//! use volatile_register::sim::{self, Config, Region, Violation, Watch};
+//!
+//! #[repr(C)]
+//! pub struct Uart {
+//!     pub cr: RW<u32>,
+//!     pub baud: RW<u32>,
+//!     pub sr: RO<u32>,
+//! }
+//!
+//! static ENABLED: AtomicBool = AtomicBool::new(false);
+//! static POLLS: AtomicUsize = AtomicUsize::new(0);
+//!
+//! fn cr_written(bytes: &[u8]) {
+//!     ENABLED.store(bytes[0] & 1 != 0, Ordering::Relaxed);
+//! }
+//!
+//! fn baud_written(_: &[u8]) {
+//!     assert!(ENABLED.load(Ordering::Relaxed), "BAUD written before CR.EN was set");
+//! }
+//!
+//! // reports "ready" on the third read
+//! fn sr_read(bytes: &mut [u8]) {
+//!     let ready = POLLS.fetch_add(1, Ordering::Relaxed) >= 2;
+//!     bytes.copy_from_slice(&u32::from(ready).to_ne_bytes());
+//! }
+//!
+//! fn on_violation(violation: &Violation) {
+//!     panic!("{:?}", violation);
+//! }
+//!
+//! static CONFIG: Config = Config {
+//!     regions: &[
+//!         Region { offset: 0, size: 8, access: Kind::ReadWrite },
+//!         Region { offset: 8, size: 4, access: Kind::ReadOnly },
+//!     ],
+//!     watches: &[
+//!         Watch { offset: 0, on_read: None, on_write: Some(cr_written) },
+//!         Watch { offset: 4, on_read: None, on_write: Some(baud_written) },
+//!         Watch { offset: 8, on_read: Some(sr_read), on_write: None },
+//!     ],
+//!     on_violation,
+//! };
+//!
+//! let mut memory = [0u32; 3];
+//! let uart = unsafe { &*(memory.as_mut_ptr() as *const Uart) };
+//!
+//! unsafe { sim::install(uart as *const Uart as usize, &CONFIG) }
+//! unsafe {
+//!     uart.cr.write(1);
+//!     uart.baud.write(115_200);
+//! }
+//! while uart.sr.read() == 0 {}
+//! assert_eq!(POLLS.load(Ordering::Relaxed), 3);
+//! ```
+
+use core::cell::Cell;
+use core::ptr;
+
+use access::Kind;
+use indirect::{self, Vtable};
+
+/// Description of the simulated register map
+pub struct Config {
+    /// The registers, in any order
+    pub regions: &'static [Region],
+    /// Callbacks attached to individual registers
+    pub watches: &'static [Watch],
+    /// Called on every access that doesn't follow the register map
+    pub on_violation: fn(&Violation),
+}
+
+/// Register, or group of registers with the same access kind
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Region {
+    /// Offset, in bytes, from the base address
+    pub offset: usize,
+    /// Size in bytes
+    pub size: usize,
+    /// Allowed accesses
+    pub access: Kind,
+}
+
+/// Callbacks attached to the register at `offset`
+///
+/// Callbacks only run for accesses that start at `offset` and follow the
+/// register map.
+#[derive(Clone, Copy, Debug)]
+pub struct Watch {
+    /// Offset, in bytes, from the base address
+    pub offset: usize,
+    /// Called on reads with the bytes about to be returned to the driver,
+    /// which it can overwrite
+    pub on_read: Option<fn(&mut [u8])>,
+    /// Called on writes with the bytes being written, before they are stored
+    pub on_write: Option<fn(&[u8])>,
+}
+
+/// Access that doesn't follow the register map
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Violation {
+    /// A write-only register was read
+    ReadOfWriteOnly {
+        /// Offset, in bytes, from the base address
+        offset: usize,
+        /// Size of the access in bytes
+        size: usize,
+    },
+    /// A read-only register was written
+    WriteToReadOnly {
+        /// Offset, in bytes, from the base address
+        offset: usize,
+        /// Size of the access in bytes
+        size: usize,
+    },
+    /// The access is not contained in any register
+    Reserved {
+        /// Offset, in bytes, from the base address; wraps around for
+        /// accesses below it
+        offset: usize,
+        /// Size of the access in bytes
+        size: usize,
+        /// `true` for writes, `false` for reads
+        write: bool,
+    },
+}
+
+std::thread_local! {
+    static STATE: Cell<Option<(usize, &'static Config)>> = const { Cell::new(None) };
+}
+
+static VTABLE: Vtable = Vtable { read, write };
+
+/// Starts checking the register accesses made by the calling thread against
+/// `config`
+///
+/// `base` is the address of the RAM the simulated register blocks live in;
+/// region offsets are relative to it.
+///
+/// # Safety
+///
+/// Reads that don't follow the register map return zeroes: zero must be a
+/// valid value of the type of the registers read that way, as it is for
+/// integers
+pub unsafe fn install(base: usize, config: &'static Config) {
+    STATE.with(|state| state.set(Some((base, config))));
+    // the other reads return the bytes of the simulated registers
+    indirect::install(&VTABLE);
+}
+
+/// Returns the base address and the register map of the calling thread
+fn state() -> Option<(usize, &'static Config)> {
+    STATE.with(Cell::get)
+}
+
+/// Looks up the watch on the register at `offset`
+fn watch(config: &Config, offset: usize) -> Option<&Watch> {
+    config.watches.iter().find(|watch| watch.offset == offset)
+}
+
+/// Looks up the access kind of the region that contains the access; `None`
+/// if it starts below the base address or isn't contained in any region
+fn lookup(config: &Config, offset: Option<usize>, size: usize) -> Option<Kind> {
+    let offset = offset?;
+    let end = offset.checked_add(size)?;
+    config
+        .regions
+        .iter()
+        .find(|region| {
+            offset >= region.offset
+                && region.offset.checked_add(region.size).is_some_and(|limit| end <= limit)
+        })
+        .map(|region| region.access)
+}
+
+fn read(address: usize, buffer: &mut [u8]) {
+    let size = buffer.len();
+    let (base, config) = match state() {
+        Some(state) => state,
+        None => {
+            unsafe { ptr::copy_nonoverlapping(address as *const u8, buffer.as_mut_ptr(), size) }
+            return;
+        }
+    };
+    let offset = address.wrapping_sub(base);
+    let violation = match lookup(config, address.checked_sub(base), size) {
+        Some(access) if access.is_readable() => None,
+        Some(_) => Some(Violation::ReadOfWriteOnly { offset, size }),
+        None => Some(Violation::Reserved { offset, size, write: false }),
+    };
+    match violation {
+        None => {
+            unsafe {
+                ptr::copy_nonoverlapping(address as *const u8, buffer.as_mut_ptr(), size)
+            }
+            if let Some(on_read) = watch(config, offset).and_then(|watch| watch.on_read) {
+                on_read(buffer)
+            }
+        }
+        Some(violation) => {
+            (config.on_violation)(&violation);
+            for byte in buffer {
+                *byte = 0;
+            }
+        }
+    }
+}
+
+fn write(address: usize, data: &[u8]) {
+    let size = data.len();
+    let (base, config) = match state() {
+        Some(state) => state,
+        None => {
+            unsafe { ptr::copy_nonoverlapping(data.as_ptr(), address as *mut u8, size) }
+            return;
+        }
+    };
+    let offset = address.wrapping_sub(base);
+    let violation = match lookup(config, address.checked_sub(base), size) {
+        Some(access) if access.is_writable() => None,
+        Some(_) => Some(Violation::WriteToReadOnly { offset, size }),
+        None => Some(Violation::Reserved { offset, size, write: true }),
+    };
+    match violation {
+        None => {
+            if let Some(on_write) = watch(config, offset).and_then(|watch| watch.on_write) {
+                on_write(data)
+            }
+            unsafe { ptr::copy_nonoverlapping(data.as_ptr(), address as *mut u8, size) }
+        }
+        Some(violation) => (config.on_violation)(&violation),
+    }
+}
@@ -0,0 +1,86 @@
+use core::mem;
+
+use {Int, ReadValue};
+
+/// Leading bytes of a serialized snapshot
+const MAGIC: [u8; 2] = *b"VR";
+/// Version of the wire format produced by `serialize_into`
+const VERSION: u8 = 1;
+/// Magic, version and width
+const HEADER: usize = 4;
+
+/// Error returned by [`ReadValue::serialize_into`] when the buffer can't
+/// hold the snapshot
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BufferTooSmall;
+
+/// Error returned by [`ReadValue::parse`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input ends before the end of the snapshot
+    Truncated,
+    /// The input doesn't start with a snapshot header
+    BadMagic,
+    /// The snapshot was produced by an unknown version of the format
+    UnsupportedVersion(u8),
+    /// The snapshot holds a value of a different width, in bytes
+    WidthMismatch(u8),
+}
+
+impl<T> ReadValue<T>
+    where T: Int
+{
+    /// Size of the serialized snapshot, in bytes
+    pub const WIRE_SIZE: usize = HEADER + mem::size_of::<T>();
+
+    /// Serializes the snapshot into the start of `buffer`, returning the
+    /// number of bytes written
+    ///
+    /// The format doesn't depend on the target: a 4-byte header (the magic
+    /// `b"VR"`, the format version and the width of the value in bytes)
+    /// followed by the value, least significant byte first. It can be decoded
+    /// with [`parse`](#method.parse) on any target, including host tooling.
+    ///
+    /// ```
+    /// use volatile_register::ReadValue;
+    ///
+    /// let mut buffer = [0; 8];
+    /// let n = ReadValue::new(0x1234_5678u32).serialize_into(&mut buffer).unwrap();
+    /// assert_eq!(&buffer[..n], b"VR\x01\x04\x78\x56\x34\x12");
+    /// assert_eq!(ReadValue::<u32>::parse(&buffer[..n]), Ok(ReadValue::new(0x1234_5678)));
+    /// ```
+    pub fn serialize_into(&self, buffer: &mut [u8]) -> Result<usize, BufferTooSmall> {
+        if buffer.len() < Self::WIRE_SIZE {
+            return Err(BufferTooSmall);
+        }
+
+        buffer[..2].copy_from_slice(&MAGIC);
+        buffer[2] = VERSION;
+        buffer[3] = mem::size_of::<T>() as u8;
+        self.get().write_le(&mut buffer[HEADER..Self::WIRE_SIZE]);
+        Ok(Self::WIRE_SIZE)
+    }
+
+    /// Parses a snapshot produced by [`serialize_into`](#method.serialize_into)
+    ///
+    /// Bytes past the end of the snapshot are ignored.
+    pub fn parse(bytes: &[u8]) -> Result<Self, ParseError> {
+        if bytes.len() < HEADER {
+            return Err(ParseError::Truncated);
+        }
+        if bytes[..2] != MAGIC {
+            return Err(ParseError::BadMagic);
+        }
+        if bytes[2] != VERSION {
+            return Err(ParseError::UnsupportedVersion(bytes[2]));
+        }
+        if usize::from(bytes[3]) != mem::size_of::<T>() {
+            return Err(ParseError::WidthMismatch(bytes[3]));
+        }
+        if bytes.len() < Self::WIRE_SIZE {
+            return Err(ParseError::Truncated);
+        }
+
+        Ok(ReadValue::new(T::read_le(&bytes[HEADER..Self::WIRE_SIZE])))
+    }
+}
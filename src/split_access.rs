@@ -0,0 +1,206 @@
+use access::{HasAccess, Kind};
+use {raw, Halves, Read, Write, RO, RW, WO};
+
+/// Register accessed as two half-width accesses, low half first
+///
+/// Some bus bridges only support 16-bit transactions, and the peripherals
+/// behind them document the order in which the halves of their 32-bit
+/// registers must be accessed, usually the low half first: writing the high
+/// half commits the new value, or reading the low half latches the high one.
+/// `SplitAccess` always performs the two accesses in that order, whatever
+/// the target's endianness, at the addresses of the halves for it.
+///
+/// [`RegisterBlock::iter_registers`](trait.RegisterBlock.html#method.iter_registers)
+/// lists it as one register of the full width, read in the same order.
+///
+/// ```
+/// #[macro_use]
+/// extern crate volatile_register;
+///
+/// use volatile_register::{Pure, RegisterBlock, SplitAccess, RW};
+///
+/// register_block! {
+///     pub struct Timer {
+///         pub count: Pure<SplitAccess<RW<u32>>>,
+///     }
+/// }
+///
+/// fn main() {
+///     let mut memory = 0u32;
+///     let timer = unsafe { &*(&mut memory as *mut u32 as *const Timer) };
+///
+///     unsafe { timer.count.write(0x1234_5678) }
+///     assert_eq!(timer.count.read(), 0x1234_5678);
+///     assert_eq!(memory, 0x1234_5678);
+///
+///     let mut registers = timer.iter_registers();
+///     let count = registers.next().unwrap();
+///     assert_eq!((count.name(), count.index(), count.info().width), ("count", None, 4));
+///     assert_eq!(count.peek(), Some(0x1234_5678));
+///     assert!(registers.next().is_none());
+/// }
+/// ```
+#[repr(transparent)]
+pub struct SplitAccess<R> {
+    register: R,
+}
+
+impl<R> HasAccess for SplitAccess<R>
+    where R: HasAccess
+{
+    const ACCESS: Option<Kind> = R::ACCESS;
+    const WIDTH: usize = R::WIDTH;
+    const PURE: bool = R::PURE;
+    const SPLIT: bool = true;
+}
+
+#[cfg(feature = "emulation")]
+impl<R> Default for SplitAccess<R>
+    where R: Default
+{
+    fn default() -> Self {
+        SplitAccess { register: R::default() }
+    }
+}
+
+impl<R> SplitAccess<R> {
+    /// Returns the address of the register
+    #[inline(always)]
+    pub fn addr(&self) -> usize {
+        self as *const SplitAccess<R> as usize
+    }
+
+    /// Returns pointers to the `(low, high)` halves of the register
+    #[inline(always)]
+    fn halves<H>(&self) -> (*mut H, *mut H) {
+        let base = self as *const SplitAccess<R> as *mut H;
+        if cfg!(target_endian = "little") {
+            (base, base.wrapping_add(1))
+        } else {
+            (base.wrapping_add(1), base)
+        }
+    }
+
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    unsafe fn read_halves<T>(&self) -> T
+        where T: Halves
+    {
+        let (low, high) = self.halves::<T::Half>();
+        let low = raw::read(low);
+        let high = raw::read(high);
+        T::join(high, low)
+    }
+
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    unsafe fn write_halves<T>(&self, value: T)
+        where T: Halves
+    {
+        let (low, high) = self.halves::<T::Half>();
+        let (high_half, low_half) = value.split();
+        raw::write(low, low_half);
+        raw::write(high, high_half);
+    }
+}
+
+impl<T> SplitAccess<RO<T>>
+    where T: Halves
+{
+    /// Reads the low half, then the high half, of the register
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub fn read(&self) -> T {
+        unsafe { self.read_halves() }
+    }
+}
+
+impl<T> SplitAccess<RW<T>>
+    where T: Halves
+{
+    /// Performs a read-modify-write operation, each access low half first
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn modify<F>(&self, f: F)
+        where F: FnOnce(T) -> T
+    {
+        self.write_halves(f(self.read_halves()))
+    }
+
+    /// Reads the low half, then the high half, of the register
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub fn read(&self) -> T {
+        unsafe { self.read_halves() }
+    }
+
+    /// Writes the low half, then the high half, of `value` into the register
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn write(&self, value: T) {
+        self.write_halves(value)
+    }
+}
+
+impl<T> SplitAccess<WO<T>>
+    where T: Halves
+{
+    /// Writes the low half, then the high half, of `value` into the register
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn write(&self, value: T) {
+        self.write_halves(value)
+    }
+}
+
+impl<T> Read<T> for SplitAccess<RO<T>>
+    where T: Halves
+{
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    fn read(&self) -> T {
+        SplitAccess::<RO<T>>::read(self)
+    }
+}
+
+impl<T> Read<T> for SplitAccess<RW<T>>
+    where T: Halves
+{
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    fn read(&self) -> T {
+        SplitAccess::<RW<T>>::read(self)
+    }
+}
+
+impl<T> Write<T> for SplitAccess<RW<T>>
+    where T: Halves
+{
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    unsafe fn write(&self, value: T) {
+        SplitAccess::<RW<T>>::write(self, value)
+    }
+}
+
+impl<T> Write<T> for SplitAccess<WO<T>>
+    where T: Halves
+{
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    unsafe fn write(&self, value: T) {
+        SplitAccess::<WO<T>>::write(self, value)
+    }
+}
@@ -0,0 +1,173 @@
+//! Register access statistics
+//!
+//! With the `stats` feature enabled every register access made through this
+//! crate is counted against the register block it falls in, to quantify the
+//! MMIO traffic of each driver when chasing bus contention or power issues.
+//! The blocks are described by a table of [`Block`]s installed at runtime;
+//! accesses outside of all of them are counted in [`unattributed`].
+//!
+//! ```
+//! use volatile_register::stats::{self, Block};
+//! use volatile_register::RW;
+//!
+//! let mut memory = [0u32; 2];
+//! let base = memory.as_mut_ptr() as usize;
+//!
+//! // `Block`s are usually `static`s, with the addresses from the datasheet
+//! let blocks: &'static [Block] = Box::leak(Box::new([
+//!     Block::new("uart0", base, 4),
+//!     Block::new("uart1", base + 4, 4),
+//! ]));
+//! stats::install(blocks).unwrap();
+//! assert_eq!(stats::install(blocks), Err(stats::AlreadyInstalled));
+//!
+//! let uart0 = unsafe { &*(base as *const RW<u32>) };
+//! unsafe { uart0.write(0x55) };
+//! uart0.read();
+//!
+//! let report = stats::report().next().unwrap();
+//! assert_eq!((report.name, report.reads, report.writes), ("uart0", 1, 1));
+//! assert_eq!(report.read_bytes + report.write_bytes, 8);
+//! ```
+//!
+//! Counters are 32 bits wide and wrap around. On targets without atomic
+//! read-modify-write instructions, like ARMv6-M, an access made by an
+//! interrupt handler while another access is being counted can be lost.
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use registry::Registry;
+
+pub use registry::AlreadyInstalled;
+
+/// Register block whose accesses are counted
+pub struct Block {
+    name: &'static str,
+    base: usize,
+    size: usize,
+    counters: Counters,
+}
+
+struct Counters {
+    reads: AtomicU32,
+    writes: AtomicU32,
+    read_bytes: AtomicU32,
+    write_bytes: AtomicU32,
+}
+
+impl Counters {
+    const fn new() -> Self {
+        Counters {
+            reads: AtomicU32::new(0),
+            writes: AtomicU32::new(0),
+            read_bytes: AtomicU32::new(0),
+            write_bytes: AtomicU32::new(0),
+        }
+    }
+
+    fn record(&self, size: usize, write: bool) {
+        if write {
+            add(&self.writes, 1);
+            add(&self.write_bytes, size as u32);
+        } else {
+            add(&self.reads, 1);
+            add(&self.read_bytes, size as u32);
+        }
+    }
+
+    fn report(&self, name: &'static str) -> Report {
+        Report {
+            name,
+            reads: self.reads.load(Ordering::Relaxed),
+            writes: self.writes.load(Ordering::Relaxed),
+            read_bytes: self.read_bytes.load(Ordering::Relaxed),
+            write_bytes: self.write_bytes.load(Ordering::Relaxed),
+        }
+    }
+
+    fn clear(&self) {
+        self.reads.store(0, Ordering::Relaxed);
+        self.writes.store(0, Ordering::Relaxed);
+        self.read_bytes.store(0, Ordering::Relaxed);
+        self.write_bytes.store(0, Ordering::Relaxed);
+    }
+}
+
+#[cfg(target_has_atomic = "32")]
+#[inline(always)]
+fn add(counter: &AtomicU32, n: u32) {
+    counter.fetch_add(n, Ordering::Relaxed);
+}
+
+#[cfg(not(target_has_atomic = "32"))]
+#[inline(always)]
+fn add(counter: &AtomicU32, n: u32) {
+    counter.store(counter.load(Ordering::Relaxed).wrapping_add(n), Ordering::Relaxed);
+}
+
+impl Block {
+    /// Describes the block called `name`, `size` bytes long, starting at
+    /// address `base`
+    pub const fn new(name: &'static str, base: usize, size: usize) -> Self {
+        Block { name, base, size, counters: Counters::new() }
+    }
+}
+
+/// Statistics of one register block
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Report {
+    /// Name of the block
+    pub name: &'static str,
+    /// Number of reads
+    pub reads: u32,
+    /// Number of writes
+    pub writes: u32,
+    /// Total size of the reads, in bytes
+    pub read_bytes: u32,
+    /// Total size of the writes, in bytes
+    pub write_bytes: u32,
+}
+
+static BLOCKS: Registry<Block> = Registry::new();
+static UNATTRIBUTED: Counters = Counters::new();
+
+/// Starts counting the accesses to `blocks`
+///
+/// The table can only be installed once: later calls fail with
+/// `AlreadyInstalled`, and keep the first table.
+pub fn install(blocks: &'static [Block]) -> Result<(), AlreadyInstalled> {
+    BLOCKS.install(blocks)
+}
+
+#[inline(always)]
+fn blocks() -> &'static [Block] {
+    BLOCKS.get()
+}
+
+/// Returns the statistics of the installed blocks, in table order
+pub fn report() -> impl Iterator<Item = Report> {
+    blocks().iter().map(|block| block.counters.report(block.name))
+}
+
+/// Returns the statistics of the accesses that fall outside of all the
+/// installed blocks
+pub fn unattributed() -> Report {
+    UNATTRIBUTED.report("unattributed")
+}
+
+/// Zeroes all the counters
+pub fn clear() {
+    for block in blocks() {
+        block.counters.clear();
+    }
+    UNATTRIBUTED.clear();
+}
+
+#[inline(always)]
+pub(crate) fn record(address: usize, size: usize, write: bool) {
+    let counters = blocks()
+        .iter()
+        .find(|block| address.wrapping_sub(block.base) < block.size)
+        .map_or(&UNATTRIBUTED, |block| &block.counters);
+    counters.record(size, write);
+}
@@ -0,0 +1,204 @@
+//! Register access timing
+//!
+//! With the `timing` feature enabled every register access made through this
+//! crate is timed with the processor's cycle counter and reported, along with
+//! the location of the call site, to a hook installed at runtime. This helps
+//! find the peripherals behind slow buses that dominate an interrupt
+//! handler's time budget.
+//!
+//! The cycle counter is:
+//!
+//! - the DWT `CYCCNT` register on ARMv7-M and ARMv8-M Mainline cores, which
+//!   has to be enabled beforehand (`DEMCR.TRCENA` and `DWT_CTRL.CYCCNTENA`),
+//! - `mcycle` on bare-metal RISC-V, which has to run in machine mode,
+//! - the time stamp counter on x86.
+//!
+//! Everywhere else, including ARMv6-M and ARMv8-M Baseline cores, which have
+//! no cycle counter, A-profile ARM and hosted RISC-V, the
+//! nanoseconds elapsed on `std::time::Instant` are used instead when the
+//! `std` feature is enabled, and the counter reads as zero otherwise. The
+//! measurements include the cost of reading the counter itself.
+//!
+//! [`CallSites`] accumulates the samples per call site:
+//!
+//! ```
+//! use std::sync::Mutex;
+//! use volatile_register::timing::{self, CallSites, Sample};
+//! use volatile_register::RW;
+//!
+//! static PROFILE: Mutex<CallSites<32>> = Mutex::new(CallSites::new());
+//!
+//! fn record(sample: &Sample) {
+//!     PROFILE.lock().unwrap().record(sample);
+//! }
+//!
+//! timing::set_hook(record);
+//!
+//! let mut memory = 0u32;
+//! let register = unsafe { &*(&mut memory as *mut u32 as *const RW<u32>) };
+//! for _ in 0..4 {
+//!     register.read();
+//! }
+//!
+//! let profile = PROFILE.lock().unwrap();
+//! let site = profile.iter().next().unwrap();
+//! assert_eq!(site.count, 4);
+//! assert_eq!(site.location.file(), file!());
+//! ```
+
+use core::mem;
+use core::panic::Location;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// Timing of one register access
+#[derive(Clone, Copy, Debug)]
+pub struct Sample {
+    /// Location of the call that performed the access
+    pub location: &'static Location<'static>,
+    /// Cycles spent in the access
+    pub cycles: u32,
+    /// Size of the access in bytes
+    pub size: usize,
+    /// `true` for writes, `false` for reads
+    pub write: bool,
+}
+
+static HOOK: AtomicUsize = AtomicUsize::new(0);
+
+/// Reports the timing of all subsequent register accesses to `hook`
+pub fn set_hook(hook: fn(&Sample)) {
+    HOOK.store(hook as usize, Ordering::Release);
+}
+
+/// Stops reporting the timing of register accesses
+pub fn clear_hook() {
+    HOOK.store(0, Ordering::Release);
+}
+
+/// Reads the cycle counter
+#[inline(always)]
+pub fn cycles() -> u32 {
+    #[cfg(all(target_arch = "arm", target_feature = "mclass", target_feature = "v7"))]
+    {
+        const DWT_CYCCNT: *const u32 = 0xE000_1004 as *const u32;
+        unsafe { core::ptr::read_volatile(DWT_CYCCNT) }
+    }
+    #[cfg(all(target_os = "none", any(target_arch = "riscv32", target_arch = "riscv64")))]
+    {
+        let cycles: usize;
+        unsafe {
+            core::arch::asm!("csrr {}, mcycle", out(reg) cycles, options(nomem, nostack));
+        }
+        cycles as u32
+    }
+    #[cfg(target_arch = "x86_64")]
+    {
+        unsafe { core::arch::x86_64::_rdtsc() as u32 }
+    }
+    #[cfg(target_arch = "x86")]
+    {
+        unsafe { core::arch::x86::_rdtsc() as u32 }
+    }
+    #[cfg(not(any(all(target_arch = "arm", target_feature = "mclass", target_feature = "v7"),
+                  all(target_os = "none",
+                      any(target_arch = "riscv32", target_arch = "riscv64")),
+                  target_arch = "x86_64",
+                  target_arch = "x86")))]
+    {
+        #[cfg(feature = "std")]
+        {
+            use std::sync::OnceLock;
+            use std::time::Instant;
+
+            static START: OnceLock<Instant> = OnceLock::new();
+            START.get_or_init(Instant::now).elapsed().as_nanos() as u32
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            0
+        }
+    }
+}
+
+#[inline(always)]
+pub(crate) fn record(location: &'static Location<'static>,
+                     start: u32,
+                     size: usize,
+                     write: bool) {
+    let cycles = cycles().wrapping_sub(start);
+    let hook = HOOK.load(Ordering::Acquire);
+    if hook != 0 {
+        let hook = unsafe { mem::transmute::<usize, fn(&Sample)>(hook) };
+        hook(&Sample { location, cycles, size, write });
+    }
+}
+
+/// Per call site accumulator of access timings, for up to `N` call sites
+///
+/// Samples from call sites beyond the first `N` are counted in `dropped`.
+pub struct CallSites<const N: usize> {
+    sites: [Option<CallSite>; N],
+    dropped: u32,
+}
+
+/// Accumulated timings of one call site
+#[derive(Clone, Copy, Debug)]
+pub struct CallSite {
+    /// Location of the call site
+    pub location: &'static Location<'static>,
+    /// Number of accesses
+    pub count: u32,
+    /// Total number of cycles spent in the accesses
+    pub cycles: u64,
+}
+
+impl<const N: usize> CallSites<N> {
+    /// Creates an empty accumulator
+    pub const fn new() -> Self {
+        CallSites { sites: [None; N], dropped: 0 }
+    }
+
+    /// Accumulates `sample` into its call site
+    pub fn record(&mut self, sample: &Sample) {
+        for site in self.sites.iter_mut() {
+            match *site {
+                Some(ref mut site) if site.location == sample.location => {
+                    site.count += 1;
+                    site.cycles += u64::from(sample.cycles);
+                    return;
+                }
+                Some(_) => {}
+                None => {
+                    *site = Some(CallSite {
+                        location: sample.location,
+                        count: 1,
+                        cycles: u64::from(sample.cycles),
+                    });
+                    return;
+                }
+            }
+        }
+        self.dropped += 1;
+    }
+
+    /// Returns the call sites seen so far, in order of first access
+    pub fn iter(&self) -> impl Iterator<Item = &CallSite> {
+        self.sites.iter().flatten()
+    }
+
+    /// Returns the number of samples that didn't fit in the accumulator
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+
+    /// Forgets all the accumulated timings
+    pub fn clear(&mut self) {
+        *self = CallSites::new();
+    }
+}
+
+impl<const N: usize> Default for CallSites<N> {
+    fn default() -> Self {
+        CallSites::new()
+    }
+}
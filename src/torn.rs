@@ -0,0 +1,149 @@
+use access::{HasAccess, Kind};
+use {raw, Read, Write, RO, RW, WO};
+
+/// Opt-in to accesses wider than the native access width
+///
+/// Accessing a register wider than what the target can access in a single
+/// bus transaction, like a `RW<u64>` on a 32-bit microcontroller, is
+/// rejected when the crate is built, because the access is performed as
+/// several narrower ones that are not atomic:
+///
+/// ``` compile_fail
+/// use volatile_register::RW;
+///
+/// let register = unsafe { &*(0x4000_0000 as *const RW<u128>) };
+/// register.read();
+/// ```
+///
+/// Wrapping the register in `Torn` acknowledges that a concurrent update can
+/// be observed half done:
+///
+/// ``` no_run
+/// use volatile_register::{Torn, RW};
+///
+/// let register = unsafe { &*(0x4000_0000 as *const Torn<RW<u128>>) };
+/// register.read();
+/// ```
+#[repr(transparent)]
+pub struct Torn<R> {
+    register: R,
+}
+
+impl<R> HasAccess for Torn<R>
+    where R: HasAccess
+{
+    const ACCESS: Option<Kind> = R::ACCESS;
+    const WIDTH: usize = R::WIDTH;
+    const PURE: bool = R::PURE;
+    const SPLIT: bool = R::SPLIT;
+}
+
+impl<R> Torn<R> {
+    /// Returns the address of the register
+    #[inline(always)]
+    pub fn addr(&self) -> usize {
+        self as *const Torn<R> as usize
+    }
+}
+
+impl<T> Torn<RO<T>>
+    where T: Copy
+{
+    /// Reads the value of the register
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub fn read(&self) -> T {
+        unsafe { raw::read_torn(self.register.register.as_ptr()) }
+    }
+}
+
+impl<T> Torn<RW<T>>
+    where T: Copy
+{
+    /// Performs a read-modify-write operation
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn modify<F>(&self, f: F)
+        where F: FnOnce(T) -> T
+    {
+        let register = self.register.register.as_ptr();
+        raw::write_torn(register, f(raw::read_torn(register)));
+    }
+
+    /// Reads the value of the register
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub fn read(&self) -> T {
+        unsafe { raw::read_torn(self.register.register.as_ptr()) }
+    }
+
+    /// Writes a `value` into the register
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn write(&self, value: T) {
+        raw::write_torn(self.register.register.as_ptr(), value)
+    }
+}
+
+impl<T> Torn<WO<T>>
+    where T: Copy
+{
+    /// Writes `value` into the register
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn write(&self, value: T) {
+        raw::write_torn(self.register.as_ptr(), value)
+    }
+}
+
+impl<T> Read<T> for Torn<RO<T>>
+    where T: Copy
+{
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    fn read(&self) -> T {
+        Torn::<RO<T>>::read(self)
+    }
+}
+
+impl<T> Read<T> for Torn<RW<T>>
+    where T: Copy
+{
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    fn read(&self) -> T {
+        Torn::<RW<T>>::read(self)
+    }
+}
+
+impl<T> Write<T> for Torn<RW<T>>
+    where T: Copy
+{
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    unsafe fn write(&self, value: T) {
+        Torn::<RW<T>>::write(self, value)
+    }
+}
+
+impl<T> Write<T> for Torn<WO<T>>
+    where T: Copy
+{
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    unsafe fn write(&self, value: T) {
+        Torn::<WO<T>>::write(self, value)
+    }
+}
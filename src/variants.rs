@@ -0,0 +1,98 @@
+/// Error returned by the `detect` constructor generated by
+/// [`variants!`](macro.variants.html) when the revision register holds an
+/// unknown revision
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnknownRevision<T> {
+    /// The value read from the revision register
+    pub revision: T,
+}
+
+/// Defines an `enum` of the layout variants of a register block, keyed by
+/// the value of a revision register
+///
+/// The macro expands to an `enum` with a variant holding a reference to the
+/// register block for each layout, and an `unsafe fn detect(address)`
+/// constructor. `detect` reads the revision register, found at the given
+/// offset from `address`, and returns the view of the layout whose pattern
+/// matches, or [`UnknownRevision`](struct.UnknownRevision.html).
+///
+/// ```
+/// #[macro_use]
+/// extern crate volatile_register;
+///
+/// use volatile_register::{UnknownRevision, RO, RW};
+///
+/// #[repr(C)]
+/// pub struct TimV1 {
+///     pub cr: RW<u32>,
+///     pub rev: RO<u32>,
+/// }
+///
+/// #[repr(C)]
+/// pub struct TimV2 {
+///     pub cr: RW<u32>,
+///     pub rev: RO<u32>,
+///     pub dither: RW<u32>,
+/// }
+///
+/// variants! {
+///     /// Timer, per IP revision
+///     pub enum Tim, revision at 0x4: u32 {
+///         /// First silicon
+///         V1(TimV1) = 1,
+///         /// Adds dithering
+///         V2(TimV2) = 2 | 3,
+///     }
+/// }
+///
+/// fn main() {
+///     let mut memory = [0, 3, 0u32];
+///     let address = memory.as_mut_ptr() as usize;
+///
+///     match unsafe { Tim::detect(address) } {
+///         Ok(Tim::V2(tim)) => unsafe { tim.dither.write(1) },
+///         Ok(Tim::V1(_)) => unreachable!(),
+///         Err(UnknownRevision { revision }) => panic!("revision {}", revision),
+///     }
+///     assert_eq!(memory[2], 1);
+/// }
+/// ```
+#[macro_export]
+macro_rules! variants {
+    ($(#[$attr:meta])*
+     $vis:vis enum $name:ident, revision at $offset:literal: $rev:ty {
+         $(
+             $(#[$variant_attr:meta])*
+             $variant:ident($block:ty) = $($revision:pat)|+
+         ),+ $(,)?
+     }) => {
+        $(#[$attr])*
+        $vis enum $name<'a> {
+            $(
+                $(#[$variant_attr])*
+                $variant(&'a $block),
+            )+
+        }
+
+        impl<'a> $name<'a> {
+            /// Reads the revision register of the block located at
+            /// `address` and returns the view of the matching layout
+            ///
+            /// # Safety
+            ///
+            /// `address` must be the address of the register block, and the
+            /// block must stay mapped for `'a`
+            pub unsafe fn detect(address: usize)
+                                 -> Result<Self, $crate::UnknownRevision<$rev>> {
+                let revision = (*((address + $offset) as *const $crate::RO<$rev>)).read();
+                match revision {
+                    $(
+                        $($revision)|+ => Ok($name::$variant(&*(address as *const $block))),
+                    )+
+                    #[allow(unreachable_patterns)]
+                    revision => Err($crate::UnknownRevision { revision }),
+                }
+            }
+        }
+    }
+}
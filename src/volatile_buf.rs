@@ -0,0 +1,191 @@
+use core::marker::PhantomData;
+
+use {barrier, raw, RW, WO};
+
+/// Region of device memory, like packet RAM or a firmware download window
+///
+/// ``` no_run
+/// use volatile_register::{copy_volatile, VolatileBuf};
+///
+/// static FIRMWARE: [u32; 4] = [0x2000_1000, 0x0800_0101, 0, 0];
+///
+/// let window = unsafe { VolatileBuf::new(0x5000_0000 as *mut u32, 1024) };
+/// unsafe { copy_volatile(&window, &FIRMWARE) }
+/// ```
+pub struct VolatileBuf<'a, T>
+    where T: Copy
+{
+    ptr: *mut T,
+    len: usize,
+    _marker: PhantomData<&'a [WO<T>]>,
+}
+
+impl<'a, T> VolatileBuf<'a, T>
+    where T: Copy
+{
+    /// Creates a region of `len` elements starting at `ptr`
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must point to `len` elements of device memory, aligned for `T`,
+    /// that stay mapped for `'a`
+    pub const unsafe fn new(ptr: *mut T, len: usize) -> Self {
+        VolatileBuf { ptr, len, _marker: PhantomData }
+    }
+
+    /// Returns the number of elements in the region
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the region has no elements
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns a raw pointer to the start of the region
+    pub fn as_ptr(&self) -> *mut T {
+        self.ptr
+    }
+
+    /// Hands the region over to the hardware, e.g. before starting a DMA
+    /// transfer that reads or writes it
+    ///
+    /// Makes the writes that precede the call visible to the hardware. The
+    /// region can't be accessed by the CPU until it's reclaimed.
+    ///
+    /// ``` no_run
+    /// use volatile_register::{copy_volatile, VolatileBuf, WO};
+    ///
+    /// let tx_ring = unsafe { VolatileBuf::new(0x2004_0000 as *mut u32, 64) };
+    /// let dma_src = unsafe { &*(0x4002_6010 as *const WO<u32>) };
+    /// let dma_start = unsafe { &*(0x4002_6000 as *const WO<u32>) };
+    ///
+    /// unsafe { copy_volatile(&tx_ring, &[0x8000_0040, 0x2000_1000]) }
+    /// let handle = tx_ring.hand_off();
+    /// unsafe {
+    ///     dma_src.write(handle.as_ptr() as u32);
+    ///     dma_start.write(1);
+    /// }
+    /// // .. wait for the transfer complete interrupt ..
+    /// let tx_ring = unsafe { handle.reclaim() };
+    /// ```
+    pub fn hand_off(self) -> DmaHandle<'a, T> {
+        barrier::write();
+        DmaHandle { buf: self }
+    }
+}
+
+/// [`VolatileBuf`] owned by the hardware
+///
+/// Returned by [`VolatileBuf::hand_off`]; gives the address of the region,
+/// to program the hardware with, but no CPU access to its contents.
+pub struct DmaHandle<'a, T>
+    where T: Copy
+{
+    buf: VolatileBuf<'a, T>,
+}
+
+impl<'a, T> DmaHandle<'a, T>
+    where T: Copy
+{
+    /// Returns the number of elements in the region
+    pub fn len(&self) -> usize {
+        self.buf.len
+    }
+
+    /// Returns `true` if the region has no elements
+    pub fn is_empty(&self) -> bool {
+        self.buf.len == 0
+    }
+
+    /// Returns a raw pointer to the start of the region
+    pub fn as_ptr(&self) -> *mut T {
+        self.buf.ptr
+    }
+
+    /// Takes the region back from the hardware
+    ///
+    /// Waits until the accesses that precede the call have completed, so
+    /// that CPU reads of the region observe what the hardware wrote.
+    ///
+    /// # Safety
+    ///
+    /// The hardware must be done with the region, e.g. the DMA transfer
+    /// must have completed or been aborted
+    pub unsafe fn reclaim(self) -> VolatileBuf<'a, T> {
+        barrier::complete();
+        self.buf
+    }
+}
+
+/// Borrows the register exclusively, so that no other `VolatileBuf` can be
+/// created over it while this one, or its [`DmaHandle`], exists
+///
+/// ```
+/// use volatile_register::{copy_volatile, VolatileBuf, RW};
+///
+/// let mut memory = [0u32; 4];
+/// let fifo = unsafe { &mut *(memory.as_mut_ptr() as *mut RW<[u32; 4]>) };
+///
+/// let window = VolatileBuf::from(&mut *fifo);
+/// unsafe { copy_volatile(&window, &[1, 2]) }
+/// let handle = window.hand_off();
+/// // `VolatileBuf::from(&mut *fifo)` doesn't compile while `handle` exists
+/// let window = unsafe { handle.reclaim() };
+/// drop(window);
+///
+/// assert_eq!(memory, [1, 2, 0, 0]);
+/// ```
+///
+/// ``` compile_fail
+/// use volatile_register::{VolatileBuf, RW};
+///
+/// let mut memory = [0u32; 4];
+/// let fifo = unsafe { &mut *(memory.as_mut_ptr() as *mut RW<[u32; 4]>) };
+///
+/// let handle = VolatileBuf::from(&mut *fifo).hand_off();
+/// let _alias = VolatileBuf::from(&mut *fifo);
+/// let _window = unsafe { handle.reclaim() };
+/// ```
+impl<'a, T, const N: usize> From<&'a mut RW<[T; N]>> for VolatileBuf<'a, T>
+    where T: Copy
+{
+    fn from(register: &'a mut RW<[T; N]>) -> Self {
+        unsafe { VolatileBuf::new(register.register.as_ptr() as *mut T, N) }
+    }
+}
+
+/// Borrows the register exclusively, so that no other `VolatileBuf` can be
+/// created over it while this one, or its [`DmaHandle`], exists
+impl<'a, T, const N: usize> From<&'a mut WO<[T; N]>> for VolatileBuf<'a, T>
+    where T: Copy
+{
+    fn from(register: &'a mut WO<[T; N]>) -> Self {
+        unsafe { VolatileBuf::new(register.as_ptr() as *mut T, N) }
+    }
+}
+
+/// Copies `src` into the start of `dst`
+///
+/// Every element is written with a volatile access, so the copy is never
+/// elided or reordered with other register accesses. With the `nightly`
+/// feature the copy uses the `volatile_copy_nonoverlapping_memory`
+/// intrinsic, which lets the compiler use wider accesses; don't enable it for
+/// regions that only accept accesses of the element width.
+///
+/// # Panics
+///
+/// If `src` is longer than `dst`.
+///
+/// # Safety
+///
+/// `unsafe` because writes to device memory are side effectful
+#[inline(always)]
+#[cfg_attr(feature = "timing", track_caller)]
+pub unsafe fn copy_volatile<T>(dst: &VolatileBuf<T>, src: &[T])
+    where T: Copy
+{
+    assert!(src.len() <= dst.len, "source longer than the destination region");
+    raw::copy(dst.ptr, src)
+}
@@ -0,0 +1,178 @@
+use core::marker::PhantomData;
+
+use access::{ReadOnly, ReadWrite, Readable, Writable, WriteOnly};
+use {raw, RO, RW, WO};
+
+/// Borrowed volatile view of a value
+///
+/// Unlike `RO`, `RW` and `WO`, which describe the memory itself, a
+/// `VolatileRef` is a pointer-sized handle that can be created from a raw
+/// pointer and handed to helper functions. Its access permissions, `A`, are
+/// part of the type and can only be narrowed: a read-write view can be turned
+/// into a read-only or a write-only view, but not the other way around.
+///
+/// ``` no_run
+/// use volatile_register::VolatileRef;
+/// use volatile_register::access::Readable;
+///
+/// fn is_busy<A: Readable>(status: VolatileRef<u32, A>) -> bool {
+///     status.read() & 1 != 0
+/// }
+///
+/// let status = unsafe { VolatileRef::<u32>::new(0x4000_0004 as *mut u32) };
+/// while is_busy(status.read_only()) {}
+/// ```
+///
+/// Round trip through RAM:
+///
+/// ```
+/// use volatile_register::{VolatileRef, WO};
+///
+/// let mut memory = [0u32; 2];
+/// let status = unsafe { VolatileRef::<u32>::new(memory.as_mut_ptr()) };
+/// unsafe { status.write(0x8000_0001) }
+/// assert_eq!(status.read(), 0x8000_0001);
+/// assert_eq!(status.read_only().read(), 0x8000_0001);
+///
+/// let data = unsafe { &*(memory.as_mut_ptr().add(1) as *const WO<u32>) };
+/// let data = VolatileRef::from(data);
+/// unsafe { data.write(0x5a) }
+/// assert_eq!(memory, [0x8000_0001, 0x5a]);
+/// ```
+///
+/// A narrowed view can't be written through:
+///
+/// ``` compile_fail
+/// use volatile_register::VolatileRef;
+///
+/// let mut memory = 0u32;
+/// let status = unsafe { VolatileRef::<u32>::new(&mut memory) };
+/// unsafe { status.read_only().write(1) }
+/// ```
+pub struct VolatileRef<'a, T, A = ReadWrite>
+    where T: Copy
+{
+    pointer: *mut T,
+    _marker: PhantomData<(&'a T, A)>,
+}
+
+impl<'a, T, A> VolatileRef<'a, T, A>
+    where T: Copy
+{
+    /// Creates a view of the value `pointer` points to
+    ///
+    /// # Safety
+    ///
+    /// `pointer` must be valid for the accesses allowed by `A`, and stay so
+    /// for the lifetime `'a`
+    #[inline(always)]
+    pub const unsafe fn new(pointer: *mut T) -> Self {
+        VolatileRef { pointer, _marker: PhantomData }
+    }
+
+    /// Returns the address of the value
+    #[inline(always)]
+    pub fn addr(&self) -> usize {
+        self.pointer as usize
+    }
+
+    /// Returns the raw pointer this view was created from
+    #[inline(always)]
+    pub fn as_ptr(&self) -> *mut T {
+        self.pointer
+    }
+}
+
+impl<'a, T, A> VolatileRef<'a, T, A>
+    where T: Copy, A: Readable
+{
+    /// Reads the value
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub fn read(&self) -> T {
+        unsafe { raw::read(self.pointer) }
+    }
+
+    /// Narrows this view to read-only access
+    #[inline(always)]
+    pub fn read_only(self) -> VolatileRef<'a, T, ReadOnly> {
+        VolatileRef { pointer: self.pointer, _marker: PhantomData }
+    }
+}
+
+impl<'a, T, A> VolatileRef<'a, T, A>
+    where T: Copy, A: Writable
+{
+    /// Writes `value`
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    pub unsafe fn write(&self, value: T) {
+        raw::write(self.pointer, value)
+    }
+
+    /// Narrows this view to write-only access
+    #[inline(always)]
+    pub fn write_only(self) -> VolatileRef<'a, T, WriteOnly> {
+        VolatileRef { pointer: self.pointer, _marker: PhantomData }
+    }
+}
+
+impl<'a, T, A> Clone for VolatileRef<'a, T, A>
+    where T: Copy
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T, A> Copy for VolatileRef<'a, T, A>
+    where T: Copy
+{}
+
+impl<'a, T> From<&'a RO<T>> for VolatileRef<'a, T, ReadOnly>
+    where T: Copy
+{
+    fn from(register: &'a RO<T>) -> Self {
+        unsafe { VolatileRef::new(register.register.as_ptr()) }
+    }
+}
+
+impl<'a, T> From<&'a RW<T>> for VolatileRef<'a, T, ReadWrite>
+    where T: Copy
+{
+    fn from(register: &'a RW<T>) -> Self {
+        unsafe { VolatileRef::new(register.register.as_ptr()) }
+    }
+}
+
+impl<'a, T> From<&'a WO<T>> for VolatileRef<'a, T, WriteOnly>
+    where T: Copy
+{
+    fn from(register: &'a WO<T>) -> Self {
+        unsafe { VolatileRef::new(register.as_ptr()) }
+    }
+}
+
+impl<'a, T, A> ::Read<T> for VolatileRef<'a, T, A>
+    where T: Copy, A: Readable
+{
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    fn read(&self) -> T {
+        VolatileRef::read(self)
+    }
+}
+
+impl<'a, T, A> ::Write<T> for VolatileRef<'a, T, A>
+    where T: Copy, A: Writable
+{
+    #[inline(always)]
+    #[cfg_attr(feature = "timing", track_caller)]
+    unsafe fn write(&self, value: T) {
+        VolatileRef::write(self, value)
+    }
+}
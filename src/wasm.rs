@@ -0,0 +1,55 @@
+//! WebAssembly simulation backend
+//!
+//! With the `wasm` feature enabled, and when compiling for `wasm32`, every
+//! register access made through this crate calls a function imported from
+//! the host, so that firmware logic can be exercised in a browser or any
+//! other WebAssembly runtime that simulates the peripherals.
+//!
+//! The host must provide the following functions in the `volatile_register`
+//! import module, with addresses, pointers and sizes passed as `i32`:
+//!
+//! - `read(address, buffer, size)`: reads `size` bytes of the register at
+//!   `address` and stores them, little endian, at `buffer` in the module's
+//!   linear memory. The bytes must be a valid value of the register's type,
+//!   which any bytes are for integer registers; `buffer` is zeroed
+//!   beforehand.
+//! - `write(address, data, size)`: writes the `size` bytes found, little
+//!   endian, at `data` in the module's linear memory to the register at
+//!   `address`.
+//!
+//! `address` is the address the register block was created at, which doesn't
+//! need to be backed by linear memory.
+//!
+//! On other architectures this feature has no effect. On `wasm32` it takes
+//! precedence over the `indirect` feature.
+
+#[cfg(target_arch = "wasm32")]
+use core::mem;
+
+#[cfg(target_arch = "wasm32")]
+use raw;
+
+#[cfg(target_arch = "wasm32")]
+mod host {
+    #[link(wasm_import_module = "volatile_register")]
+    extern "C" {
+        pub fn read(address: usize, buffer: *mut u8, size: usize);
+        pub fn write(address: usize, data: *const u8, size: usize);
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+#[inline(always)]
+pub(crate) unsafe fn read<T>(src: *const T) -> T
+    where T: Copy
+{
+    raw::read_bytes(|buffer| host::read(src as usize, buffer.as_mut_ptr(), buffer.len()))
+}
+
+#[cfg(target_arch = "wasm32")]
+#[inline(always)]
+pub(crate) unsafe fn write<T>(dst: *mut T, value: T)
+    where T: Copy
+{
+    host::write(dst as usize, &value as *const T as *const u8, mem::size_of::<T>());
+}
@@ -0,0 +1,168 @@
+use {barrier, Read, Write};
+
+/// Journal of register writes committed in order
+///
+/// Initialization sequences often program dozens of registers that must be
+/// written in a specific order, and not before the memory they refer to has
+/// been set up. A `WriteBatch` records up to `N` `(register, value)` pairs and
+/// `commit`s them in the order they were pushed, with a single fence before
+/// the first write and another one after the last.
+///
+/// ``` no_run
+/// use volatile_register::{RW, WO, WriteBatch};
+///
+/// #[repr(C)]
+/// pub struct Timer {
+///     pub cr: RW<u32>,
+///     pub psc: WO<u32>,
+///     pub arr: WO<u32>,
+/// }
+///
+/// let timer = unsafe { &*(0x4000_0000 as *const Timer) };
+///
+/// let mut batch = WriteBatch::<u32, 3>::new();
+/// batch.push(&timer.psc, 47).unwrap();
+/// batch.push(&timer.arr, 999).unwrap();
+/// batch.push(&timer.cr, 1).unwrap();
+/// unsafe { batch.commit() }
+/// ```
+pub struct WriteBatch<'a, T, const N: usize>
+    where T: Copy
+{
+    writes: [Option<(&'a dyn Write<T>, T)>; N],
+    len: usize,
+}
+
+impl<'a, T, const N: usize> WriteBatch<'a, T, N>
+    where T: Copy
+{
+    /// Creates an empty batch
+    pub const fn new() -> Self {
+        WriteBatch { writes: [None; N], len: 0 }
+    }
+
+    /// Records a write of `value` into `register`
+    ///
+    /// Nothing is written until the batch is committed. Returns
+    /// `Err(BatchFull)` if `N` writes have already been recorded.
+    pub fn push<R>(&mut self, register: &'a R, value: T) -> Result<(), BatchFull>
+        where R: Write<T>
+    {
+        if self.len == N {
+            return Err(BatchFull);
+        }
+        self.writes[self.len] = Some((register, value));
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Writes `value` into `register` right away, and records the write so
+    /// that it can be replayed later with [`commit`](#method.commit)
+    ///
+    /// Recording the writes of an initialization routine captures the
+    /// resulting configuration as a compact list, that can be re-applied
+    /// after a peripheral reset or a wake-up from deep sleep without running
+    /// the routine again.
+    ///
+    /// Returns `Err(BatchFull)`, without writing, if `N` writes have already
+    /// been recorded.
+    ///
+    /// ```
+    /// use volatile_register::{RW, WriteBatch};
+    ///
+    /// #[repr(C)]
+    /// pub struct Timer {
+    ///     pub cr: RW<u32>,
+    ///     pub psc: RW<u32>,
+    /// }
+    ///
+    /// let mut memory = [0u32; 2];
+    /// let timer = unsafe { &*(memory.as_mut_ptr() as *const Timer) };
+    ///
+    /// let mut replay = WriteBatch::<u32, 4>::new();
+    /// unsafe {
+    ///     replay.record(&timer.psc, 47).unwrap();
+    ///     replay.record_modify(&timer.cr, |cr| cr | 1).unwrap();
+    /// }
+    ///
+    /// // .. the peripheral is reset ..
+    /// unsafe {
+    ///     timer.cr.write(0);
+    ///     timer.psc.write(0);
+    /// }
+    ///
+    /// unsafe { replay.commit() }
+    /// assert_eq!((timer.cr.read(), timer.psc.read()), (1, 47));
+    /// ```
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    pub unsafe fn record<R>(&mut self, register: &'a R, value: T) -> Result<(), BatchFull>
+        where R: Write<T>
+    {
+        self.push(register, value)?;
+        register.write(value);
+        Ok(())
+    }
+
+    /// Performs a read-modify-write operation on `register` right away, and
+    /// records the write of the new value, like [`record`](#method.record)
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    pub unsafe fn record_modify<R, F>(&mut self, register: &'a R, f: F) -> Result<(), BatchFull>
+        where R: Read<T> + Write<T>, F: FnOnce(T) -> T
+    {
+        if self.len == N {
+            return Err(BatchFull);
+        }
+        self.record(register, f(register.read()))
+    }
+
+    /// Returns the number of recorded writes
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no write has been recorded
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Discards all the recorded writes
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Performs the recorded writes, in order
+    ///
+    /// Memory operations that precede the call are completed before the
+    /// first write, and the writes are completed before any memory operation
+    /// that follows the call. The batch is left untouched and can be
+    /// committed again.
+    ///
+    /// # Safety
+    ///
+    /// `unsafe` because writes to a register are side effectful
+    pub unsafe fn commit(&self) {
+        barrier::full();
+        for &(register, value) in self.writes[..self.len].iter().flatten() {
+            register.write(value);
+        }
+        barrier::complete();
+    }
+}
+
+impl<'a, T, const N: usize> Default for WriteBatch<'a, T, N>
+    where T: Copy
+{
+    fn default() -> Self {
+        WriteBatch::new()
+    }
+}
+
+/// Error returned by [`WriteBatch::push`] when the batch is full
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BatchFull;